@@ -0,0 +1,89 @@
+//! Two clock outputs: 25 MHz on OUT1 and 40 MHz on OUT2, OUT3 and OUT4 disabled
+//!
+//! 10 MHz reference on CLKIN, 2.7 GHz VCO, 5P49V6965 at the default address.
+//! Prints the achieved frequencies and errors, then idles.
+
+#![no_std]
+#![no_main]
+
+use embassy_executor::Spawner;
+use embassy_time::Timer;
+
+use embassy_rp::i2c::{Config, I2c, InterruptHandler};
+
+use defmt_rtt as _;
+use panic_probe as _;
+
+use renesas_5p49v_rs::{
+    DefmtLogger, OutputChannel, Pll5p49v6965, Pll5p49vConfig, PllFrequencyPlan,
+};
+
+embassy_rp::bind_interrupts!(struct Irqs {
+    I2C1_IRQ => InterruptHandler<embassy_rp::peripherals::I2C1>;
+});
+
+#[embassy_executor::main]
+async fn main(_spawner: Spawner) {
+    let p = embassy_rp::init(Default::default());
+
+    let i2c = I2c::new_async(p.I2C1, p.PIN_15, p.PIN_14, Irqs, Config::default());
+
+    let config = match Pll5p49vConfig::builder()
+        .clock(10000000)
+        .vco(2700000000)
+        .output1(25000000)
+        .output2(40000000)
+        .build()
+    {
+        Ok(config) => config
+            .with_output_disabled(OutputChannel::Out3)
+            .with_output_disabled(OutputChannel::Out4),
+        Err(e) => {
+            defmt::error!("Invalid configuration: {}", e);
+            return;
+        }
+    };
+
+    let plan = PllFrequencyPlan::from_config(config.clone());
+    defmt::info!("{}", plan);
+
+    let pll = match Pll5p49v6965::new_probed(i2c).await {
+        Ok(pll) => pll.with_logger(DefmtLogger),
+        Err((_, e)) => {
+            defmt::error!("PLL not found: {}", e);
+            return;
+        }
+    };
+
+    let pll = match pll.write_config(&config).await {
+        Ok(pll) => pll,
+        Err((_, e)) => {
+            defmt::error!("Error programming PLL: {}", e);
+            return;
+        }
+    };
+
+    let mut pll = match pll.calibrate_vco().await {
+        Ok(pll) => pll,
+        Err((_, e)) => {
+            defmt::error!("Error calibrating VCO: {}", e);
+            return;
+        }
+    };
+
+    for output in [OutputChannel::Out1, OutputChannel::Out2] {
+        match pll.read_output_frequency(output, config.clock_fq_hz).await {
+            Ok(actual_hz) => defmt::info!(
+                "OUT{}: {} Hz, {} ppm",
+                output.index() + 1,
+                actual_hz,
+                plan.error_ppm[output.index()]
+            ),
+            Err(e) => defmt::error!("Error reading OUT{}: {}", output.index() + 1, e),
+        }
+    }
+
+    loop {
+        Timer::after_secs(1).await;
+    }
+}
@@ -0,0 +1,116 @@
+//! Timing of `write_config` and `calibrate_vco` on the actual bus
+//!
+//! Helps choosing the I2C bus speed (100 kHz, 400 kHz, 1 MHz) for a latency
+//! budget. Times include the fixed 1 ms waits of the VCO calibration.
+
+use embassy_time::Instant;
+use embedded_hal::digital::OutputPin;
+
+use crate::{
+    Calibrated, Gen6, Pll5p49v, Pll5p49vConfig, Pll5p49vError, PllCalibrationParams, PllLogger,
+    DEFAULT_CALIBRATION_RETRIES,
+};
+
+/// Minimum, mean and maximum duration of an operation, microseconds
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OperationTiming {
+    pub min_us: u64,
+    pub mean_us: u64,
+    pub max_us: u64,
+}
+
+/// Result of `PllBenchmark::run`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BenchmarkResults {
+    /// Number of completed rounds
+    pub iterations: u32,
+    /// Full configuration write
+    pub write_config: OperationTiming,
+    /// VCO calibration
+    pub calibrate_vco: OperationTiming,
+}
+
+/// Running min / sum / max of one operation
+#[derive(Clone, Copy, Default)]
+struct TimingAccumulator {
+    min_us: u64,
+    total_us: u64,
+    max_us: u64,
+    count: u32,
+}
+
+impl TimingAccumulator {
+    fn add(&mut self, elapsed_us: u64) {
+        self.min_us = if self.count == 0 {
+            elapsed_us
+        } else {
+            self.min_us.min(elapsed_us)
+        };
+        self.max_us = self.max_us.max(elapsed_us);
+        self.total_us += elapsed_us;
+        self.count += 1;
+    }
+
+    fn timing(&self) -> OperationTiming {
+        OperationTiming {
+            min_us: self.min_us,
+            mean_us: self.total_us.checked_div(self.count as u64).unwrap_or(0),
+            max_us: self.max_us,
+        }
+    }
+}
+
+/// Measures `write_config` and `calibrate_vco` over a number of rounds
+#[derive(Default)]
+pub struct PllBenchmark {
+    write_config: TimingAccumulator,
+    calibrate_vco: TimingAccumulator,
+}
+
+impl PllBenchmark {
+    /// Benchmark without measurements
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write `config` and calibrate the VCO `iterations` times
+    ///
+    /// Every round writes the full register set, changed or not. The driver
+    /// is left calibrated for `config`. Stops at the first failure.
+    pub async fn run<I2C, E, L: PllLogger, O: OutputPin>(
+        &mut self,
+        pll: &mut Pll5p49v<I2C, Gen6, Calibrated, L, O>,
+        config: &Pll5p49vConfig,
+        iterations: u32,
+    ) -> Result<BenchmarkResults, Pll5p49vError<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    {
+        *self = Self::new();
+
+        for _ in 0..iterations {
+            pll.invalidate_cache();
+
+            let start = Instant::now();
+            pll.write_prog_array(config).await?;
+            self.write_config.add(start.elapsed().as_micros());
+
+            let start = Instant::now();
+            pll.run_calibration(
+                &mut embassy_time::Delay,
+                &PllCalibrationParams::DEFAULT,
+                DEFAULT_CALIBRATION_RETRIES,
+            )
+            .await?;
+            self.calibrate_vco.add(start.elapsed().as_micros());
+        }
+
+        Ok(BenchmarkResults {
+            iterations,
+            write_config: self.write_config.timing(),
+            calibrate_vco: self.calibrate_vco.timing(),
+        })
+    }
+}
@@ -0,0 +1,234 @@
+//! Blocking driver for `embedded-hal` I2C, enabled by the `blocking` feature
+//!
+//! Register contents come from the same `build_prog_array` as the async driver.
+
+use core::marker::PhantomData;
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::{
+    build_prog_array, regs, unwritten_registers, Address, ChipGeneration, Gen5, Gen6,
+    Pll5p49vConfig, Pll5p49vError, PllCalibrationParams, RegisterCache,
+    DEFAULT_CALIBRATION_RETRIES, PROG_ARRAY_SIZE,
+};
+
+/// Blocking 5P49V clock generator driver
+///
+/// Takes the I2C bus by value like `Pll5p49v`, `release` hands it back.
+pub struct Pll5p49vBlocking<I2C, G: ChipGeneration> {
+    i2c: I2C,
+    address: u8,
+    _generation: PhantomData<G>,
+}
+
+/// Chip generation and I2C address, the bus is not printed
+impl<I2C, G: ChipGeneration> core::fmt::Debug for Pll5p49vBlocking<I2C, G> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pll5p49vBlocking")
+            .field("generation", &G::GENERATION)
+            .field("address", &format_args!("{:#04x}", self.address))
+            .finish()
+    }
+}
+
+/// Chip generation and I2C address, the bus is not printed
+#[cfg(feature = "defmt")]
+impl<I2C, G: ChipGeneration> defmt::Format for Pll5p49vBlocking<I2C, G> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Pll5p49vBlocking({}, address: {=u8:#04x})",
+            G::GENERATION,
+            self.address
+        );
+    }
+}
+
+impl<I2C, G: ChipGeneration> Pll5p49vBlocking<I2C, G> {
+    /// Create a new driver instance for the default I2C address 0x6A
+    ///
+    /// The bus is not accessed, a missing chip shows up at the first
+    /// transfer, as with `Pll5p49v::new_unchecked`.
+    pub fn new_unchecked(i2c: I2C) -> Self {
+        Self::new_with_address(i2c, Address::Default)
+    }
+
+    /// Create a new driver instance for the given I2C address
+    pub fn new_with_address(i2c: I2C, address: Address) -> Self {
+        Self {
+            i2c,
+            address: address.addr(),
+            _generation: PhantomData,
+        }
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+}
+
+impl<I2C, E, G: ChipGeneration> Pll5p49vBlocking<I2C, G>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Read a single register
+    pub fn read_register(&mut self, reg: u8) -> Result<u8, Pll5p49vError<E>> {
+        let mut resp_buff = [0u8; 1];
+        self.i2c
+            .write_read(self.address, &[reg], &mut resp_buff)
+            .map_err(Pll5p49vError::I2c)?;
+
+        Ok(resp_buff[0])
+    }
+
+    /// Read consecutive registers starting at `reg` in a single I2C burst
+    pub fn read_registers(&mut self, reg: u8, buf: &mut [u8]) -> Result<(), Pll5p49vError<E>> {
+        self.i2c
+            .write_read(self.address, &[reg], buf)
+            .map_err(Pll5p49vError::I2c)
+    }
+
+    /// Write a single register
+    ///
+    /// Warning: bypasses the typed API, see `Pll5p49v::write_register`.
+    pub fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Pll5p49vError<E>> {
+        self.i2c
+            .write(self.address, &[reg, value])
+            .map_err(Pll5p49vError::I2c)
+    }
+
+    /// Update the bits selected by `mask` in a register, other bits are preserved
+    pub fn read_modify_write(
+        &mut self,
+        reg: u8,
+        mask: u8,
+        value: u8,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let current = self.read_register(reg)?;
+
+        self.write_register(reg, (current & !mask) | (value & mask))
+    }
+
+    /// Read the VCO band index found by the last calibration (register 0x99, bits 7:3)
+    pub fn read_vco_index(&mut self) -> Result<u8, Pll5p49vError<E>> {
+        let vco_status = self.read_register(regs::VCO_STATUS)?;
+
+        Ok(vco_status >> 3)
+    }
+
+    /// Write configuration registers and wait `Pll5p49vConfig::stabilization_ms`
+    ///
+    /// The same registers as the first `Pll5p49v::write_config`: every
+    /// register except the dividers of disabled outputs, in one burst per run.
+    pub fn write_config_blocking<D: DelayNs>(
+        &mut self,
+        config: &Pll5p49vConfig,
+        delay: &mut D,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let prog_array = build_prog_array(config)?;
+
+        // prog_array starts with the register address
+        let values = &prog_array[1..];
+        let skip = unwritten_registers(config);
+
+        let mut from = 0;
+
+        while let Some((start, end)) = RegisterCache::EMPTY.next_changed_run(values, &skip, from) {
+            let mut burst = [0u8; PROG_ARRAY_SIZE];
+            burst[0] = start as u8;
+            burst[1..=end - start].copy_from_slice(&values[start..end]);
+
+            self.i2c
+                .write(self.address, &burst[..=end - start])
+                .map_err(Pll5p49vError::I2c)?;
+
+            from = end;
+        }
+
+        delay.delay_ms(config.stabilization_ms);
+
+        Ok(())
+    }
+}
+
+impl<I2C, E> Pll5p49vBlocking<I2C, Gen5>
+where
+    I2C: I2c<Error = E>,
+{
+    /// No-op: 5th generation devices calibrate the VCO automatically after programming
+    pub fn calibrate_vco_blocking<D: DelayNs>(
+        &mut self,
+        _delay: &mut D,
+    ) -> Result<(), Pll5p49vError<E>> {
+        Ok(())
+    }
+}
+
+impl<I2C, E> Pll5p49vBlocking<I2C, Gen6>
+where
+    I2C: I2c<Error = E>,
+{
+    /// Calibrate VCO, required after programming on 6th generation devices
+    ///
+    /// Same procedure as `Pll5p49v::calibrate_vco`: `PllCalibrationParams::DEFAULT`
+    /// timing, up to `DEFAULT_CALIBRATION_RETRIES` retries.
+    pub fn calibrate_vco_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Pll5p49vError<E>> {
+        self.calibrate_vco_blocking_with_params(
+            delay,
+            &PllCalibrationParams::DEFAULT,
+            DEFAULT_CALIBRATION_RETRIES,
+        )?;
+
+        Ok(())
+    }
+
+    /// Calibrate VCO with the given pulse timing and retries, returns the VCO index
+    ///
+    /// After each calibration the index (register 0x99) is read twice 1 ms
+    /// apart. A differing index triggers another calibration, after
+    /// `max_retries` repetitions the result is `CalibrationUnstable`.
+    pub fn calibrate_vco_blocking_with_params<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        params: &PllCalibrationParams,
+        max_retries: u8,
+    ) -> Result<u8, Pll5p49vError<E>> {
+        let mut last_index = 0;
+
+        for _ in 0..=max_retries {
+            // Write 0-1-0 to the 7th bit of the register 0x1C
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, 0)?;
+
+            delay.delay_us(params.reset_assert_us);
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, regs::CAL_START_BIT)?;
+
+            delay.delay_us(params.reset_hold_us);
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, 0)?;
+
+            delay.delay_ms(params.post_cal_settle_ms);
+
+            let vco_index = self.read_vco_index()?;
+
+            delay.delay_ms(1);
+
+            last_index = self.read_vco_index()?;
+
+            if vco_index == last_index {
+                return Ok(vco_index);
+            }
+        }
+
+        Err(Pll5p49vError::CalibrationUnstable {
+            retries: max_retries,
+            last_index,
+        })
+    }
+}
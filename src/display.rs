@@ -0,0 +1,306 @@
+//! `core::fmt::Display` for the configuration types, enabled by the `display` feature
+//!
+//! Text counterpart of the `defmt::Format` impls for host-side tools, e.g.
+//! to print a frequency plan for review before flashing.
+//!
+//! Covers the configuration, its field types and the configuration errors.
+//! Driver state and raw register views (`OutputStatus`, `PllDiagnostics`,
+//! `OutputDividerRegs`, `ChipVariantInfo`, `Pll5p49vError`, ...) only have
+//! `Debug`, their fields are register values rather than settings.
+
+use core::fmt;
+
+use crate::{
+    CalibrationTrigger, ClockOutputConfig, ClockSource, ConfigError, DriveStrength,
+    EthernetPhyInterface, GlobalOutputConfig, InputFailover, OePolarity, OutputChannel,
+    OutputFormat, OutputVoltage, ParseError, Pll5p49vConfig, PllFrequencyPlan, Polarity,
+    SigmaDeltaOrder, SlewRate, SpreadSpectrum,
+};
+
+impl fmt::Display for ClockSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClockSource::ExternalClockInput => write!(f, "CLKIN"),
+            ClockSource::Crystal => write!(f, "crystal"),
+        }
+    }
+}
+
+impl fmt::Display for InputFailover {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.enabled {
+            write!(f, "{}, failover to {}", self.primary, self.fallback)
+        } else {
+            write!(f, "off")
+        }
+    }
+}
+
+impl fmt::Display for SigmaDeltaOrder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigmaDeltaOrder::Bypass => write!(f, "bypass"),
+            SigmaDeltaOrder::Order1 => write!(f, "1st order"),
+            SigmaDeltaOrder::Order2 => write!(f, "2nd order"),
+            SigmaDeltaOrder::Order3 => write!(f, "3rd order"),
+            SigmaDeltaOrder::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+impl fmt::Display for CalibrationTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalibrationTrigger::WriteWithCalStart => write!(f, "written with CAL_START set"),
+            CalibrationTrigger::WriteWithoutCalStart => write!(f, "written with CAL_START clear"),
+            CalibrationTrigger::SeparateCalibrate => write!(f, "separate calibrate_vco"),
+        }
+    }
+}
+
+impl fmt::Display for OePolarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OePolarity::ActiveHigh => write!(f, "active high"),
+            OePolarity::ActiveLow => write!(f, "active low"),
+        }
+    }
+}
+
+impl fmt::Display for OutputChannel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "OUT{}", self.index() + 1)
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Lvpecl => write!(f, "LVPECL"),
+            OutputFormat::Lvcmos => write!(f, "LVCMOS"),
+            OutputFormat::Hcsl => write!(f, "HCSL"),
+            OutputFormat::Lvds => write!(f, "LVDS"),
+        }
+    }
+}
+
+impl fmt::Display for DriveStrength {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DriveStrength::Ma8 => write!(f, "8 mA"),
+            DriveStrength::Ma12 => write!(f, "12 mA"),
+            DriveStrength::Ma16 => write!(f, "16 mA"),
+            DriveStrength::Ma24 => write!(f, "24 mA"),
+        }
+    }
+}
+
+impl fmt::Display for SlewRate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SlewRate::Fast => write!(f, "fast"),
+            SlewRate::Medium => write!(f, "medium"),
+            SlewRate::Slow => write!(f, "slow"),
+        }
+    }
+}
+
+impl fmt::Display for Polarity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Polarity::Normal => write!(f, "normal"),
+            Polarity::Inverted => write!(f, "inverted"),
+        }
+    }
+}
+
+/// Format, then drive strength and slew rate where the format uses them
+impl fmt::Display for ClockOutputConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format)?;
+
+        if self.format == OutputFormat::Lvcmos {
+            write!(f, " {}", self.drive_strength)?;
+        }
+
+        if self.slew_rate.field(self.format).is_some() {
+            write!(f, ", {} slew", self.slew_rate)?;
+        }
+
+        write!(f, ", {} polarity", self.polarity)?;
+
+        if !self.enabled {
+            write!(f, ", off")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for EthernetPhyInterface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EthernetPhyInterface::Rmii25MHz => write!(f, "RMII 25 MHz"),
+            EthernetPhyInterface::Rgmii125MHz => write!(f, "RGMII 125 MHz"),
+            EthernetPhyInterface::Sgmii125MHz => write!(f, "SGMII 125 MHz"),
+        }
+    }
+}
+
+impl fmt::Display for OutputVoltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputVoltage::V33 => write!(f, "3.3 V"),
+            OutputVoltage::V18 => write!(f, "1.8 V"),
+        }
+    }
+}
+
+impl fmt::Display for GlobalOutputConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.voltage)
+    }
+}
+
+impl fmt::Display for SpreadSpectrum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpreadSpectrum::Disabled => write!(f, "off"),
+            SpreadSpectrum::CenterSpread { deviation_ppm } => {
+                write!(f, "center +/-{} ppm", deviation_ppm)
+            }
+            SpreadSpectrum::DownSpread { deviation_ppm } => {
+                write!(f, "down -{} ppm", deviation_ppm)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Pll5p49vConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.input_failover.enabled {
+            writeln!(
+                f,
+                "input: {} Hz ({})",
+                self.clock_fq_hz, self.input_failover
+            )?;
+        } else {
+            writeln!(f, "input: {} Hz ({})", self.clock_fq_hz, self.clock_source)?;
+        }
+        writeln!(f, "VCO: {} Hz", self.vco_fq_hz)?;
+
+        for (output, clock_output) in OutputChannel::iter().zip(&self.clock_outputs) {
+            match self.outputs[output.index()] {
+                Some(output_fq_hz) => {
+                    writeln!(f, "{}: {} Hz, {}", output, output_fq_hz, clock_output)?
+                }
+                None if self.enable_ref_out == Some(output) => {
+                    writeln!(f, "{}: reference bypass, {}", output, clock_output)?
+                }
+                None => writeln!(f, "{}: disabled", output)?,
+            }
+        }
+
+        writeln!(f, "spread spectrum: {}", self.spread_spectrum)?;
+        writeln!(f, "sigma-delta modulator: {}", self.sigma_delta_order)?;
+        writeln!(f, "calibration: {}", self.calibration_trigger)?;
+
+        if self.test_mode_vco_band {
+            writeln!(f, "VCO band: {:#04x}", self.vco_band)?;
+        }
+
+        writeln!(
+            f,
+            "VCO monitor: {}",
+            if self.vco_monitor { "on" } else { "off" }
+        )?;
+        writeln!(f, "SD/OE pin: {}", self.oe_polarity)?;
+        writeln!(f, "outputs: {}", self.global_output)?;
+        write!(f, "stabilization: {} ms", self.stabilization_ms)
+    }
+}
+
+/// Table of requested vs. actual frequency and error per output
+impl fmt::Display for PllFrequencyPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "output requested_hz actual_hz error_ppm")?;
+
+        for output in 0..4 {
+            match self.config.outputs[output] {
+                Some(output_fq_hz) => write!(
+                    f,
+                    "\nOUT{} {} {} {}",
+                    output + 1,
+                    output_fq_hz,
+                    self.actual_output_hz[output],
+                    self.error_ppm[output]
+                )?,
+                None => write!(f, "\nOUT{} disabled", output + 1)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::NoClockSource => write!(f, "no input enabled"),
+            ParseError::FeedbackDividerZero => write!(f, "feedback divider is zero"),
+            ParseError::OutputDividerZero { output } => {
+                write!(f, "OUT{}: output divider is zero", output + 1)
+            }
+            ParseError::OutputFormatReserved { output } => {
+                write!(f, "OUT{}: reserved output format", output + 1)
+            }
+            ParseError::ChecksumMismatch => write!(f, "checksum mismatch"),
+            ParseError::Config(e) => write!(f, "stored configuration: {}", e),
+        }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::ClockZero => write!(f, "input clock frequency is zero"),
+            ConfigError::VcoOutOfRange => write!(f, "VCO frequency out of range"),
+            ConfigError::FeedbackDividerOverflow => write!(f, "feedback divider overflow"),
+            ConfigError::OutputDividerOutOfRange {
+                channel,
+                requested_hz,
+            } => write!(
+                f,
+                "OUT{}: no output divider for {} Hz",
+                channel + 1,
+                requested_hz
+            ),
+            ConfigError::SpreadDeviationOutOfRange => {
+                write!(f, "spread spectrum deviation out of range")
+            }
+            ConfigError::VcoBandOutOfRange => write!(f, "VCO band above 0x1F"),
+            ConfigError::OutputCountOutOfRange => write!(f, "1 - 4 outputs expected"),
+            ConfigError::ClockFrequencyOutOfRange { hz, min, max } => {
+                write!(f, "input clock {} Hz outside of {} - {} Hz", hz, min, max)
+            }
+            ConfigError::FailoverSourcesEqual => {
+                write!(f, "failover needs two different inputs")
+            }
+            ConfigError::SigmaDeltaRequiredForFractional => {
+                write!(
+                    f,
+                    "fractional feedback divider needs the sigma-delta modulator"
+                )
+            }
+            ConfigError::FeatureUnsupportedOnVariant => {
+                write!(f, "feature not supported on the chip variant")
+            }
+            ConfigError::RefOutUnavailable { channel } => {
+                write!(f, "OUT{}: no reference bypass", channel + 1)
+            }
+            ConfigError::LvdsAt1v8 { channel } => {
+                write!(f, "OUT{}: LVDS not supported at 1.8 V", channel + 1)
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! PLL state changes as a stream of events
+//!
+//! `pll_event_monitor_task` checks the chip like `pll_monitor_task` and
+//! reports what it sees through a `PllEventChannel`, `PllEventReceiver`
+//! reads the events, e.g. in another task.
+
+use embassy_sync::blocking_mutex::raw::RawMutex;
+use embassy_sync::channel::{Channel, Receiver};
+use embassy_time::Timer;
+use embedded_hal::digital::OutputPin;
+
+use crate::{
+    Calibrated, Gen6, Pll5p49v, Pll5p49vConfig, Pll5p49vError, PllCalibrationParams, PllLogger,
+    DEFAULT_CALIBRATION_RETRIES,
+};
+
+/// State change reported by `pll_event_monitor_task`
+///
+/// The chip has no lock-detect bit, a lost configuration (see
+/// `Pll5p49v::verify_config`) and the calibrated VCO band (register 0x99,
+/// bits 7:3) are what can be observed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PllEvent<E> {
+    /// The dividers read back differ from the configuration, it is written again
+    ConfigurationLost,
+    /// VCO band index changed without a calibration by the monitor
+    VcoIndexChanged { previous: u8, current: u8 },
+    /// VCO calibration after reprogramming finished
+    CalibrationComplete { vco_index: u8 },
+    /// Reprogramming failed, it is retried on the next check
+    ErrorWritingConfig(Pll5p49vError<E>),
+}
+
+/// Queue of up to `N` events between the monitor and the receiver
+///
+/// Typically a `static` with `CriticalSectionRawMutex` or, within one
+/// executor, `NoopRawMutex`.
+pub type PllEventChannel<M, E, const N: usize> = Channel<M, PllEvent<E>, N>;
+
+/// Reading end of a `PllEventChannel`
+pub struct PllEventReceiver<'a, M: RawMutex, E, const N: usize> {
+    receiver: Receiver<'a, M, PllEvent<E>, N>,
+}
+
+impl<'a, M: RawMutex, E, const N: usize> PllEventReceiver<'a, M, E, N> {
+    /// Receiver for the events `pll_event_monitor_task` sends to `channel`
+    pub fn new(channel: &'a PllEventChannel<M, E, N>) -> Self {
+        Self {
+            receiver: channel.receiver(),
+        }
+    }
+
+    /// Wait for the next event
+    pub async fn next(&mut self) -> PllEvent<E> {
+        self.receiver.receive().await
+    }
+}
+
+/// `pll_monitor_task` reporting to `events`
+///
+/// Sends `ConfigurationLost` and the outcome of reprogramming,
+/// `ErrorWritingConfig` or `CalibrationComplete`, while the chip does not
+/// hold `config`. While it does, a VCO band index differing from the last
+/// one seen is sent as `VcoIndexChanged`, the first check only records it.
+/// Events are dropped while the channel is full, so a slow receiver never
+/// stalls the recovery. Failed reads are skipped.
+pub async fn pll_event_monitor_task<
+    I2C,
+    E,
+    L: PllLogger,
+    O: OutputPin,
+    M: RawMutex,
+    const N: usize,
+>(
+    pll: &mut Pll5p49v<I2C, Gen6, Calibrated, L, O>,
+    config: &Pll5p49vConfig,
+    interval_ms: u32,
+    events: &PllEventChannel<M, E, N>,
+) -> !
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    let mut last_index = None;
+
+    loop {
+        Timer::after_millis(interval_ms as u64).await;
+
+        match pll.verify_config(config).await {
+            Ok(()) => {
+                let Ok(current) = pll.read_vco_index().await else {
+                    continue;
+                };
+
+                if let Some(previous) = last_index.filter(|&previous| previous != current) {
+                    let _ = events.try_send(PllEvent::VcoIndexChanged { previous, current });
+                }
+
+                last_index = Some(current);
+
+                continue;
+            }
+            Err(Pll5p49vError::Mismatch { .. }) => {}
+            Err(_) => continue,
+        }
+
+        let _ = events.try_send(PllEvent::ConfigurationLost);
+
+        pll.logger.log_configuration_lost();
+
+        // The cache describes the registers before the loss, write all of them
+        pll.invalidate_cache();
+
+        // Stays `Calibrated`: a failed attempt is repeated on the next check
+        match pll.write_prog_array(config).await {
+            Ok(()) => {
+                if let Ok(vco_index) = pll
+                    .run_calibration(
+                        &mut embassy_time::Delay,
+                        &PllCalibrationParams::DEFAULT,
+                        DEFAULT_CALIBRATION_RETRIES,
+                    )
+                    .await
+                {
+                    let _ = events.try_send(PllEvent::CalibrationComplete { vco_index });
+                    last_index = Some(vco_index);
+                }
+            }
+            Err(e) => {
+                let _ = events.try_send(PllEvent::ErrorWritingConfig(e));
+            }
+        }
+    }
+}
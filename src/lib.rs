@@ -0,0 +1,4598 @@
+//! Renesas 5P49V (VersaClock 5 / 6) clock generator driver
+//!
+//! Async driver for any `embedded-hal-async` I2C bus, a blocking variant is
+//! available with the `blocking` feature.
+
+#![cfg_attr(not(test), no_std)]
+
+use core::marker::PhantomData;
+
+use embassy_time::Timer;
+use embedded_hal::digital::OutputPin;
+
+pub mod benchmark;
+pub mod events;
+pub mod regs;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+#[cfg(feature = "display")]
+mod display;
+
+#[cfg(feature = "report")]
+extern crate alloc;
+
+#[cfg(feature = "report")]
+pub mod report;
+
+/// PLL configuration: input clock, VCO and output frequencies
+///
+/// Comparable with `==`, e.g. to skip reprogramming when the configuration
+/// has not changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pll5p49vConfig {
+    /// Input (reference) clock frequency, Hz
+    pub clock_fq_hz: u32,
+    /// VCO frequency, Hz
+    pub vco_fq_hz: u32,
+    /// Output 1..4 frequencies, Hz
+    ///
+    /// `None` disables the output (register 0x68) and `write_config` leaves
+    /// its divider registers untouched.
+    pub outputs: [Option<u32>; 4],
+    /// Reference input, replaced by `input_failover.primary` when failover is enabled
+    pub clock_source: ClockSource,
+    /// Automatic switch to a second reference input on loss of the primary one
+    pub input_failover: InputFailover,
+    /// Spread spectrum modulation of the outputs
+    pub spread_spectrum: SpreadSpectrum,
+    /// VCO band, 0x00 - 0x1F (register 0x11, bits 4:0)
+    ///
+    /// Used only with `test_mode_vco_band`, otherwise the band is selected by
+    /// the VCO calibration. Bands split the VCO range, a higher code selects a
+    /// higher frequency band. The index found by calibration for the target
+    /// VCO frequency (register 0x99, bits 7:3) is a good manual value.
+    pub vco_band: u8,
+    /// Use `vco_band` instead of the calibrated band (register 0x11, bit 5)
+    pub test_mode_vco_band: bool,
+    /// Polarity of the SD/OE pin (SP, register 0x10, bit 1)
+    pub oe_polarity: OePolarity,
+    /// Order of the feedback divider sigma-delta modulator (register 0x18, bits 3:2)
+    pub sigma_delta_order: SigmaDeltaOrder,
+    /// Calibration start bit written by `write_config` (register 0x1C, bit 7)
+    pub calibration_trigger: CalibrationTrigger,
+    /// VCO monitor (register 0x1D, bit 1), not working on the 5P49V6965
+    ///
+    /// `Pll5p49vConfigBuilder::build` accepts it only for a variant with
+    /// `supports_vco_monitor`.
+    pub vco_monitor: bool,
+    /// Output driven by the reference input instead of its divider, OUT1 only
+    ///
+    /// Sets SEL_EXT and clears EN_FOD of the output divider control register
+    /// (see `OutputMode::Bypass`). The output is enabled even without an
+    /// `outputs` frequency. Like `vco_monitor`, `Pll5p49vConfigBuilder::build`
+    /// accepts it only for a variant with `supports_ref_out`.
+    pub enable_ref_out: Option<OutputChannel>,
+    /// Format, drive strength, polarity, slew rate and enable of OUT1..OUT4
+    /// (registers 0x60 - 0x68)
+    pub clock_outputs: [ClockOutputConfig; 4],
+    /// Output enables and output voltage (registers 0x68 - 0x69)
+    pub global_output: GlobalOutputConfig,
+    /// Wait after `write_config` for the PLL to lock, ms
+    ///
+    /// 0 returns right after the write, the outputs may not be stable yet.
+    /// Not stored in the chip.
+    pub stabilization_ms: u32,
+}
+
+/// Default `Pll5p49vConfig::stabilization_ms`
+pub const DEFAULT_STABILIZATION_MS: u32 = 10;
+
+impl Default for Pll5p49vConfig {
+    fn default() -> Self {
+        DEFAULT_CONFIG_6965
+    }
+}
+
+/// Known-good configuration for the 5P49V6965 (6th generation)
+///
+/// 10 MHz on CLKIN, 2.7 GHz VCO. Run `calibrate_vco` after writing it, the VCO
+/// monitor stays disabled as it does not work on this chip.
+pub const DEFAULT_CONFIG_6965: Pll5p49vConfig = Pll5p49vConfig {
+    clock_fq_hz: 10000000,
+    vco_fq_hz: 2700000000,
+    outputs: [
+        Some(40000000),
+        Some(25000000),
+        Some(24000000),
+        Some(28800000),
+    ],
+    clock_source: ClockSource::ExternalClockInput,
+    input_failover: InputFailover::DISABLED,
+    spread_spectrum: SpreadSpectrum::Disabled,
+    vco_band: 0x0D,
+    test_mode_vco_band: false,
+    oe_polarity: OePolarity::ActiveHigh,
+    sigma_delta_order: SigmaDeltaOrder::Auto,
+    calibration_trigger: CalibrationTrigger::WriteWithCalStart,
+    vco_monitor: false,
+    enable_ref_out: None,
+    clock_outputs: [ClockOutputConfig::DEFAULT; 4],
+    global_output: GlobalOutputConfig::DEFAULT,
+    stabilization_ms: DEFAULT_STABILIZATION_MS,
+};
+
+/// Known-good configuration for the 5P49V5923 (5th generation)
+///
+/// 25 MHz crystal, 2.5 GHz VCO in the middle of the 5th generation range,
+/// integer feedback and output dividers. The VCO calibrates automatically.
+pub const DEFAULT_CONFIG_5923: Pll5p49vConfig = Pll5p49vConfig {
+    clock_fq_hz: 25000000,
+    vco_fq_hz: 2500000000,
+    outputs: [
+        Some(100000000),
+        Some(125000000),
+        Some(50000000),
+        Some(25000000),
+    ],
+    clock_source: ClockSource::Crystal,
+    input_failover: InputFailover::DISABLED,
+    spread_spectrum: SpreadSpectrum::Disabled,
+    vco_band: 0x0D,
+    test_mode_vco_band: false,
+    oe_polarity: OePolarity::ActiveHigh,
+    sigma_delta_order: SigmaDeltaOrder::Auto,
+    calibration_trigger: CalibrationTrigger::WriteWithCalStart,
+    vco_monitor: false,
+    enable_ref_out: None,
+    clock_outputs: [ClockOutputConfig::DEFAULT; 4],
+    global_output: GlobalOutputConfig::DEFAULT,
+    stabilization_ms: DEFAULT_STABILIZATION_MS,
+};
+
+/// Reference input of the PLL
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ClockSource {
+    /// External clock on the CLKIN pin
+    ExternalClockInput,
+    /// Crystal on the XIN/XOUT pins
+    Crystal,
+}
+
+impl ClockSource {
+    /// EN_XTAL bit, register 0x10
+    const fn en_xtal(self) -> bool {
+        matches!(self, ClockSource::Crystal)
+    }
+
+    /// EN_CLKIN bit, register 0x10
+    const fn en_clkin(self) -> bool {
+        matches!(self, ClockSource::ExternalClockInput)
+    }
+
+    /// PRIMSRC bit, register 0x13: CLKIN is the primary source
+    const fn primsrc(self) -> bool {
+        matches!(self, ClockSource::ExternalClockInput)
+    }
+
+    /// Supported input frequency range (min, max), Hz
+    pub const fn frequency_range_hz(self) -> (u32, u32) {
+        match self {
+            ClockSource::ExternalClockInput => (1000000, 200000000),
+            ClockSource::Crystal => (8000000, 40000000),
+        }
+    }
+}
+
+/// Input failover: with both inputs enabled the chip runs from `primary` and
+/// switches to `fallback` when the primary signal is lost
+///
+/// Both inputs are enabled in register 0x10, PRIMSRC (register 0x13, bit 1)
+/// selects the primary one. Both inputs are expected at `clock_fq_hz`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InputFailover {
+    /// Enable both inputs with failover
+    pub enabled: bool,
+    /// Input used while its signal is present
+    pub primary: ClockSource,
+    /// Input taking over on loss of the primary signal, must differ from `primary`
+    pub fallback: ClockSource,
+}
+
+impl InputFailover {
+    /// Failover off, only `Pll5p49vConfig::clock_source` is enabled
+    pub const DISABLED: Self = InputFailover {
+        enabled: false,
+        primary: ClockSource::ExternalClockInput,
+        fallback: ClockSource::Crystal,
+    };
+}
+
+impl Default for InputFailover {
+    fn default() -> Self {
+        Self::DISABLED
+    }
+}
+
+impl Pll5p49vConfig {
+    /// EN_XTAL, EN_CLKIN (register 0x10) and PRIMSRC (register 0x13) bits
+    const fn input_bits(&self) -> (bool, bool, bool) {
+        if self.input_failover.enabled {
+            (true, true, self.input_failover.primary.primsrc())
+        } else {
+            (
+                self.clock_source.en_xtal(),
+                self.clock_source.en_clkin(),
+                self.clock_source.primsrc(),
+            )
+        }
+    }
+
+    /// Input the chip runs from while both signals are present
+    const fn primary_source(&self) -> ClockSource {
+        if self.input_failover.enabled {
+            self.input_failover.primary
+        } else {
+            self.clock_source
+        }
+    }
+}
+
+/// Feedback divider sigma-delta modulator order
+///
+/// A higher order pushes the fractional spurs further from the carrier at
+/// the cost of more high-frequency phase noise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SigmaDeltaOrder {
+    /// Modulator off, integer feedback divider only
+    Bypass,
+    Order1,
+    Order2,
+    Order3,
+    /// Off for an integer feedback divider, 3rd order otherwise
+    Auto,
+}
+
+impl SigmaDeltaOrder {
+    /// Order field value for a 32.32 feedback divider, 0 is bypass
+    const fn bits(self, feedback_divider: u64) -> u8 {
+        match self {
+            SigmaDeltaOrder::Bypass => 0,
+            SigmaDeltaOrder::Order1 => 1,
+            SigmaDeltaOrder::Order2 => 2,
+            SigmaDeltaOrder::Order3 => 3,
+            SigmaDeltaOrder::Auto if feedback_divider & 0xFFFFFFFF == 0 => 0,
+            SigmaDeltaOrder::Auto => 3,
+        }
+    }
+
+    /// Decode the order field of register 0x18
+    const fn from_reg(value: u8) -> Self {
+        match (value >> 2) & 0b11 {
+            0 => SigmaDeltaOrder::Bypass,
+            1 => SigmaDeltaOrder::Order1,
+            2 => SigmaDeltaOrder::Order2,
+            _ => SigmaDeltaOrder::Order3,
+        }
+    }
+}
+
+/// Calibration start bit (register 0x1C, bit 7) in the `write_config` burst
+///
+/// The calibration runs on a 0 to 1 transition of the bit, a configuration
+/// written with the bit already set does not recalibrate by itself. 6th
+/// generation parts need `calibrate_vco` after `write_config` in any case,
+/// it pulses the bit 0-1-0.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CalibrationTrigger {
+    /// Bit set, the factory default configuration
+    WriteWithCalStart,
+    /// Bit cleared
+    WriteWithoutCalStart,
+    /// Bit cleared, `calibrate_vco` is called after `write_config`
+    ///
+    /// Same registers as `WriteWithoutCalStart`, states the two-step
+    /// sequence the `Configured` -> `Calibrated` states already require.
+    SeparateCalibrate,
+}
+
+impl CalibrationTrigger {
+    /// Value of the calibration start bit
+    const fn cal_start(self) -> bool {
+        matches!(self, CalibrationTrigger::WriteWithCalStart)
+    }
+
+    /// Decode register 0x1C, a cleared bit reads as `WriteWithoutCalStart`
+    const fn from_reg(value: u8) -> Self {
+        if value & regs::CAL_START_BIT != 0 {
+            CalibrationTrigger::WriteWithCalStart
+        } else {
+            CalibrationTrigger::WriteWithoutCalStart
+        }
+    }
+}
+
+/// Output voltage of the LVCMOS outputs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OutputVoltage {
+    /// 3.3 V
+    V33,
+    /// 1.8 V, not supported with LVDS outputs (see `ConfigError::LvdsAt1v8`)
+    V18,
+}
+
+/// Output voltage, register 0x69
+///
+/// The output enables in register 0x68 follow `Pll5p49vConfig::outputs` and
+/// `ClockOutputConfig::enabled`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct GlobalOutputConfig {
+    /// Output voltage
+    pub voltage: OutputVoltage,
+}
+
+impl GlobalOutputConfig {
+    /// 3.3 V
+    pub const DEFAULT: GlobalOutputConfig = GlobalOutputConfig {
+        voltage: OutputVoltage::V33,
+    };
+
+    /// Register 0x69 value
+    const fn output_ctrl_reg(self) -> u8 {
+        match self.voltage {
+            OutputVoltage::V33 => 0xFC,
+            OutputVoltage::V18 => 0xFC | regs::OUTPUT_CTRL_V18,
+        }
+    }
+
+    fn from_reg(output_ctrl: u8) -> GlobalOutputConfig {
+        let voltage = if output_ctrl & regs::OUTPUT_CTRL_V18 != 0 {
+            OutputVoltage::V18
+        } else {
+            OutputVoltage::V33
+        };
+
+        GlobalOutputConfig { voltage }
+    }
+}
+
+impl Default for GlobalOutputConfig {
+    fn default() -> Self {
+        GlobalOutputConfig::DEFAULT
+    }
+}
+
+/// Spread spectrum modulation frequency, Hz
+const SS_MODULATION_HZ: u32 = 31500;
+
+/// Spread spectrum modulation profile
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SpreadSpectrum {
+    /// No modulation
+    Disabled,
+    /// Frequency swings by +/- `deviation_ppm` around the nominal value, up to 12500 ppm
+    CenterSpread { deviation_ppm: u16 },
+    /// Frequency swings from the nominal value down by `deviation_ppm`, up to 25000 ppm
+    DownSpread { deviation_ppm: u16 },
+}
+
+impl SpreadSpectrum {
+    /// Check the deviation against the supported range
+    const fn is_valid(self) -> bool {
+        match self {
+            SpreadSpectrum::Disabled => true,
+            SpreadSpectrum::CenterSpread { deviation_ppm } => {
+                deviation_ppm > 0 && deviation_ppm <= 12500
+            }
+            SpreadSpectrum::DownSpread { deviation_ppm } => {
+                deviation_ppm > 0 && deviation_ppm <= 25000
+            }
+        }
+    }
+
+    /// Output divider with the spread applied: (start divider, 24-bit step, 13-bit period)
+    ///
+    /// The divider ramps up from the start value and back in a triangle of
+    /// `SS_MODULATION_HZ`, the period is counted in quarters of the modulation cycle.
+    fn divider_params(self, od: u64, output_fq_hz: u32) -> (u64, u32, u16) {
+        let (span_ppm, offset_ppm) = match self {
+            SpreadSpectrum::Disabled => return (od, 0, 0),
+            SpreadSpectrum::CenterSpread { deviation_ppm } => {
+                (2 * deviation_ppm as u64, deviation_ppm as u64)
+            }
+            SpreadSpectrum::DownSpread { deviation_ppm } => (deviation_ppm as u64, 0),
+        };
+
+        let start = od - od * offset_ppm / 1000000;
+        let span = od * span_ppm / 1000000;
+
+        let period = (output_fq_hz / (4 * SS_MODULATION_HZ)).clamp(1, 0x1FFF);
+
+        // Step is in units of the 24-bit divider fraction
+        let step = ((span >> 8) / (2 * period as u64)).min(0xFFFFFF) as u32;
+
+        (start, step, period as u16)
+    }
+}
+
+/// Register map parsing errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseError {
+    /// Neither CLKIN nor crystal input is enabled (register 0x10)
+    NoClockSource,
+    /// Feedback divider is zero (registers 0x17 - 0x1B)
+    FeedbackDividerZero,
+    /// Output divider is zero (output index 0..3)
+    OutputDividerZero { output: usize },
+    /// Output format bits hold a reserved value (output index 0..3)
+    OutputFormatReserved { output: usize },
+    /// Stored configuration does not match its checksum (see `from_bytes`)
+    ChecksumMismatch,
+    /// Stored configuration has dividers the chip can not be programmed
+    /// with, e.g. an erased all-zero storage (see `from_bytes`)
+    Config(ConfigError),
+}
+
+/// Feedback divider from registers 0x17 - 0x1B, 32.32 fixed point
+fn unpack_feedback_divider(regs: &[u8; 5]) -> u64 {
+    let integer = ((regs[0] as u64) << 4) | ((regs[1] as u64) >> 4);
+    let fraction = ((regs[2] as u64) << 24) | ((regs[3] as u64) << 16) | ((regs[4] as u64) << 8);
+
+    (integer << 32) | fraction
+}
+
+/// Output divider from registers 0xX2 - 0xXE of an output, 32.32 fixed point
+fn unpack_output_divider(regs: &[u8; 13]) -> u64 {
+    let integer = ((regs[11] as u64) << 4) | ((regs[12] as u64) >> 4);
+    let fraction = (((regs[0] & 0b11) as u64) << 30)
+        | ((regs[1] as u64) << 22)
+        | ((regs[2] as u64) << 14)
+        | (((regs[3] & 0b11111100) as u64) << 6);
+
+    (integer << 32) | fraction
+}
+
+/// Output divider as read from the chip (see `read_output_dividers`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutputDividerRegs {
+    /// Integer part, 12 bits
+    pub integer: u32,
+    /// Fractional part in units of 2^-32, the chip stores bits 31:8
+    pub fractional: u32,
+}
+
+impl OutputDividerRegs {
+    /// Divider as a 32.32 fixed point value, as used by `compute_actual_frequency`
+    pub const fn divider(self) -> u64 {
+        ((self.integer as u64) << 32) | self.fractional as u64
+    }
+}
+
+/// VCO frequency produced by a feedback divider, rounded to the nearest Hz
+/// (the lowest fraction byte is not stored in the chip)
+fn vco_frequency(feedback_divider: u64, clock_fq_hz: u32) -> u32 {
+    ((feedback_divider as u128 * clock_fq_hz as u128 + (1 << 31)) >> 32) as u32
+}
+
+impl Pll5p49vConfig {
+    /// Reconstruct the configuration from a register dump (see `dump_registers`)
+    ///
+    /// The input clock frequency is not stored in the chip and has to be given.
+    /// Spread spectrum is decoded as down spread, the center spread offset is
+    /// part of the divider value and can not be told apart.
+    pub fn from_registers(
+        dump: &[u8; REGISTER_MAP_SIZE],
+        clock_fq_hz: u32,
+    ) -> Result<Pll5p49vConfig, ParseError> {
+        let en_xtal = dump[regs::PRIMARY_SRC_SHDN as usize] & regs::PRIMARY_SRC_SHDN_EN_XTAL != 0;
+        let en_clkin = dump[regs::PRIMARY_SRC_SHDN as usize] & regs::PRIMARY_SRC_SHDN_EN_CLKIN != 0;
+        let primsrc = dump[regs::XTAL_X2_LOAD_CAP as usize] & regs::XTAL_X2_LOAD_CAP_PRIMSRC != 0;
+
+        let clock_source = match (en_xtal, en_clkin) {
+            (false, false) => return Err(ParseError::NoClockSource),
+            (true, false) => ClockSource::Crystal,
+            (false, true) => ClockSource::ExternalClockInput,
+            (true, true) if primsrc => ClockSource::ExternalClockInput,
+            (true, true) => ClockSource::Crystal,
+        };
+
+        // Both inputs enabled is the failover setup
+        let input_failover = if en_xtal && en_clkin {
+            InputFailover {
+                enabled: true,
+                primary: clock_source,
+                fallback: match clock_source {
+                    ClockSource::ExternalClockInput => ClockSource::Crystal,
+                    ClockSource::Crystal => ClockSource::ExternalClockInput,
+                },
+            }
+        } else {
+            InputFailover::DISABLED
+        };
+
+        let mut feedback_regs = [0u8; 5];
+        feedback_regs.copy_from_slice(
+            &dump[regs::FEEDBACK_DIV_INT_HI as usize..=regs::FEEDBACK_DIV_FRAC_2 as usize],
+        );
+
+        let feedback_divider = unpack_feedback_divider(&feedback_regs);
+
+        if feedback_divider == 0 {
+            return Err(ParseError::FeedbackDividerZero);
+        }
+
+        let vco_fq_hz = vco_frequency(feedback_divider, clock_fq_hz);
+
+        let global_output = GlobalOutputConfig::from_reg(dump[regs::OUTPUT_CTRL as usize]);
+
+        let output_en = dump[regs::OUTPUT_EN as usize];
+
+        let enable_ref_out = (dump[regs::OD1_CONTROL as usize] & OutputMode::MASK
+            == OutputMode::Bypass.bits())
+        .then_some(OutputChannel::Out1);
+
+        let mut outputs = [None; 4];
+        let mut spread_span_ppm = 0u64;
+
+        for (output, output_fq_hz) in outputs.iter_mut().enumerate() {
+            let base = (regs::OD1_FRAC_0 + regs::OD_STRIDE * output as u8) as usize;
+
+            let mut od_regs = [0u8; 13];
+            od_regs.copy_from_slice(&dump[base..base + 13]);
+
+            let od = unpack_output_divider(&od_regs);
+
+            // A disabled or bypassed output without a divider is a skipped output
+            let bypassed = enable_ref_out.is_some_and(|ref_out| ref_out.index() == output);
+
+            let enabled = output_en & (regs::OUTPUT_EN_OUT1 >> output) != 0;
+
+            if od == 0 && (bypassed || !enabled) {
+                continue;
+            }
+
+            if od == 0 {
+                return Err(ParseError::OutputDividerZero { output });
+            }
+
+            *output_fq_hz = Some(compute_actual_frequency(vco_fq_hz, od));
+
+            // Spread enable, step (0xX6 - 0xX8) and period (0xX9 - 0xXA)
+            if od_regs[3] & regs::OD_FRAC_3_SPREAD_EN != 0 {
+                let step =
+                    ((od_regs[4] as u64) << 16) | ((od_regs[5] as u64) << 8) | od_regs[6] as u64;
+                let period = ((od_regs[7] as u64) << 5) | ((od_regs[8] as u64) >> 3);
+                let span = (step * 2 * period) << 8;
+
+                spread_span_ppm = spread_span_ppm.max(span * 1000000 / od);
+            }
+        }
+
+        let spread_spectrum = if spread_span_ppm == 0 {
+            SpreadSpectrum::Disabled
+        } else {
+            SpreadSpectrum::DownSpread {
+                deviation_ppm: spread_span_ppm.min(u16::MAX as u64) as u16,
+            }
+        };
+
+        let mut clock_outputs = [ClockOutputConfig::DEFAULT; 4];
+
+        for (output, clock_output) in OutputChannel::iter().zip(clock_outputs.iter_mut()) {
+            let cfg_0 = dump[output.config_reg() as usize];
+            let cfg_1 = dump[output.config_reg() as usize + 1];
+
+            let format = OutputFormat::from_reg(cfg_0).ok_or(ParseError::OutputFormatReserved {
+                output: output.index(),
+            })?;
+
+            *clock_output = ClockOutputConfig {
+                format,
+                drive_strength: DriveStrength::from_reg(cfg_0),
+                polarity: if cfg_1 & regs::CLK_CFG_1_NORMAL_POLARITY != 0 {
+                    Polarity::Normal
+                } else {
+                    Polarity::Inverted
+                },
+                slew_rate: SlewRate::from_reg(cfg_0, format),
+                enabled: output_en & output.enable_mask() != 0,
+            };
+        }
+
+        Ok(Pll5p49vConfig {
+            clock_fq_hz,
+            vco_fq_hz,
+            outputs,
+            clock_source,
+            input_failover,
+            spread_spectrum,
+            vco_band: dump[regs::VCO_BAND as usize] & regs::VCO_BAND_MASK,
+            test_mode_vco_band: dump[regs::VCO_BAND as usize] & regs::VCO_BAND_TEST_MODE != 0,
+            oe_polarity: if dump[regs::PRIMARY_SRC_SHDN as usize] & regs::PRIMARY_SRC_SHDN_SP != 0 {
+                OePolarity::ActiveLow
+            } else {
+                OePolarity::ActiveHigh
+            },
+            sigma_delta_order: SigmaDeltaOrder::from_reg(dump[regs::FEEDBACK_DIV_INT_LO as usize]),
+            calibration_trigger: CalibrationTrigger::from_reg(dump[regs::CAL_START as usize]),
+            vco_monitor: dump[regs::VCO_MONITOR as usize] & regs::VCO_MONITOR_EN != 0,
+            enable_ref_out,
+            clock_outputs,
+            global_output,
+            stabilization_ms: DEFAULT_STABILIZATION_MS,
+        })
+    }
+}
+
+/// Size of the storage format of `Pll5p49vConfig::to_bytes`
+pub const CONFIG_BYTES_SIZE: usize = 25;
+
+impl Pll5p49vConfig {
+    /// Pack the frequencies into a storage format, e.g. for an EEPROM
+    ///
+    /// Input clock, VCO and output 1..4 frequencies as little endian `u32`
+    /// (0 for a disabled output), followed by a checksum byte making the sum of all bytes zero. Other
+    /// settings are not stored. Unrelated to the chip register format.
+    pub fn to_bytes(&self) -> [u8; CONFIG_BYTES_SIZE] {
+        let mut bytes = [0u8; CONFIG_BYTES_SIZE];
+
+        let frequencies = [self.clock_fq_hz, self.vco_fq_hz]
+            .into_iter()
+            .chain(self.outputs.map(|output| output.unwrap_or(0)));
+
+        for (chunk, fq_hz) in bytes.chunks_exact_mut(4).zip(frequencies) {
+            chunk.copy_from_slice(&fq_hz.to_le_bytes());
+        }
+
+        bytes[CONFIG_BYTES_SIZE - 1] = checksum(&bytes[..CONFIG_BYTES_SIZE - 1]);
+
+        bytes
+    }
+
+    /// Unpack frequencies stored by `to_bytes`, other settings are the defaults
+    ///
+    /// The result is checked like the configuration in `write_config`, an
+    /// all-zero buffer has a valid checksum but no input clock.
+    pub fn from_bytes(bytes: &[u8; CONFIG_BYTES_SIZE]) -> Result<Pll5p49vConfig, ParseError> {
+        if checksum(bytes) != 0 {
+            return Err(ParseError::ChecksumMismatch);
+        }
+
+        let mut frequencies = [0u32; 6];
+
+        for (fq_hz, chunk) in frequencies.iter_mut().zip(bytes.chunks_exact(4)) {
+            *fq_hz = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+
+        let config = Pll5p49vConfig {
+            clock_fq_hz: frequencies[0],
+            vco_fq_hz: frequencies[1],
+            outputs: [
+                frequencies[2],
+                frequencies[3],
+                frequencies[4],
+                frequencies[5],
+            ]
+            .map(|output_fq_hz| (output_fq_hz != 0).then_some(output_fq_hz)),
+            ..Pll5p49vConfig::default()
+        };
+
+        check_dividers(&config).map_err(ParseError::Config)?;
+
+        Ok(config)
+    }
+}
+
+/// Two's complement of the byte sum
+fn checksum(bytes: &[u8]) -> u8 {
+    bytes
+        .iter()
+        .fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+        .wrapping_neg()
+}
+
+/// Chip generation, determines the VCO operating range
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Generation {
+    /// 5th generation (5P49V59xx)
+    Fifth,
+    /// 6th generation (5P49V69xx)
+    Sixth,
+}
+
+impl Generation {
+    /// VCO operating range (min, max), Hz
+    pub const fn vco_range_hz(self) -> (u32, u32) {
+        match self {
+            Generation::Fifth => (2300000000, 2800000000),
+            Generation::Sixth => (1600000000, 3200000000),
+        }
+    }
+}
+
+/// Capabilities of a chip part number, see `SUPPORTED_VARIANTS`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChipVariantInfo {
+    /// Part number without package and temperature suffixes, e.g. "5P49V6965"
+    pub part_number: &'static str,
+    /// Chip generation, selects e.g. the VCO calibration procedure
+    pub generation: Generation,
+    /// Lower end of the VCO range, Hz
+    pub vco_min_hz: u32,
+    /// Upper end of the VCO range, Hz
+    pub vco_max_hz: u32,
+    /// VCO monitor (register 0x1D, bit 1) works on this part
+    pub supports_vco_monitor: bool,
+    /// Reference bypass on OUT1, see `Pll5p49vConfig::enable_ref_out`
+    pub supports_ref_out: bool,
+}
+
+impl ChipVariantInfo {
+    const fn new(
+        part_number: &'static str,
+        generation: Generation,
+        supports_vco_monitor: bool,
+        supports_ref_out: bool,
+    ) -> Self {
+        let (vco_min_hz, vco_max_hz) = generation.vco_range_hz();
+
+        Self {
+            part_number,
+            generation,
+            vco_min_hz,
+            vco_max_hz,
+            supports_vco_monitor,
+            supports_ref_out,
+        }
+    }
+
+    /// Look up a part number in `SUPPORTED_VARIANTS`, case sensitive
+    pub fn for_part(part: &str) -> Option<&'static ChipVariantInfo> {
+        SUPPORTED_VARIANTS
+            .iter()
+            .find(|variant| variant.part_number == part)
+    }
+}
+
+/// Part numbers the driver is known to work with
+///
+/// The VCO monitor does not work on the 5P49V6965, the 5th generation has
+/// none. All of them have the OUT1 reference bypass.
+pub const SUPPORTED_VARIANTS: &[ChipVariantInfo] = &[
+    ChipVariantInfo::new("5P49V5923", Generation::Fifth, false, true),
+    ChipVariantInfo::new("5P49V6901", Generation::Sixth, true, true),
+    ChipVariantInfo::new("5P49V6965", Generation::Sixth, false, true),
+    ChipVariantInfo::new("5P49V6975", Generation::Sixth, true, true),
+];
+
+/// Configuration validation errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ConfigError {
+    /// Input clock frequency is zero
+    ClockZero,
+    /// VCO frequency is outside of the operating range of the chip generation
+    VcoOutOfRange,
+    /// Feedback divider integer part does not fit into 12 bits (registers 0x17 - 0x18)
+    FeedbackDividerOverflow,
+    /// Output divider integer part is zero or does not fit into 12 bits
+    /// (output index 0..3 and its requested frequency)
+    OutputDividerOutOfRange { channel: usize, requested_hz: u32 },
+    /// Spread spectrum deviation is outside of the supported range
+    SpreadDeviationOutOfRange,
+    /// VCO band is above 0x1F
+    VcoBandOutOfRange,
+    /// Number of requested outputs is not 1 - 4
+    OutputCountOutOfRange,
+    /// Input clock frequency is outside of the range of the selected clock source
+    ClockFrequencyOutOfRange { hz: u32, min: u32, max: u32 },
+    /// Input failover with the same primary and fallback input
+    FailoverSourcesEqual,
+    /// Sigma-delta modulator bypassed with a fractional feedback divider
+    SigmaDeltaRequiredForFractional,
+    /// Feature not available on the chip variant, or no variant given (see
+    /// `Pll5p49vConfigBuilder::variant`)
+    FeatureUnsupportedOnVariant,
+    /// Reference bypass requested on an output other than OUT1 (index 0..3)
+    RefOutUnavailable { channel: usize },
+    /// LVDS output (index 0..3) with the outputs at 1.8 V
+    LvdsAt1v8 { channel: usize },
+}
+
+/// Builder for `Pll5p49vConfig`, starts from the default configuration
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Pll5p49vConfigBuilder {
+    config: Pll5p49vConfig,
+    generation: Generation,
+    variant: Option<&'static ChipVariantInfo>,
+}
+
+impl Pll5p49vConfig {
+    /// Start building a validated configuration
+    pub fn builder() -> Pll5p49vConfigBuilder {
+        Pll5p49vConfigBuilder::new()
+    }
+
+    /// Disable an output, its divider registers are not written
+    pub fn with_output_disabled(mut self, output: OutputChannel) -> Self {
+        self.outputs[output.index()] = None;
+        self
+    }
+
+    /// Register 0x68 value, bits other than the OUT1..OUT4 enables are kept set
+    ///
+    /// An output is enabled when `ClockOutputConfig::enabled` is set and it has
+    /// a frequency or is the reference bypass. This includes
+    /// `regs::OUTPUT_EN_OUT0_REF`, the reference output stays on.
+    fn output_enable_reg(&self) -> u8 {
+        let mut value = !regs::OUTPUT_EN_MASK;
+
+        for output in OutputChannel::iter() {
+            let running =
+                self.outputs[output.index()].is_some() || self.enable_ref_out == Some(output);
+
+            if running && self.clock_outputs[output.index()].enabled {
+                value |= output.enable_mask();
+            }
+        }
+
+        value
+    }
+}
+
+impl Default for Pll5p49vConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Pll5p49vConfigBuilder {
+    /// New builder, validates against the 5th generation VCO range by default
+    pub fn new() -> Self {
+        Self {
+            config: Pll5p49vConfig::default(),
+            generation: Generation::Fifth,
+            variant: None,
+        }
+    }
+
+    /// Chip part (see `SUPPORTED_VARIANTS`), sets the generation and enables
+    /// the checks of variant specific features
+    pub fn variant(mut self, variant: &'static ChipVariantInfo) -> Self {
+        self.generation = variant.generation;
+        self.variant = Some(variant);
+        self
+    }
+
+    /// Chip generation used for VCO range validation
+    pub fn generation(mut self, generation: Generation) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Input clock frequency, Hz
+    pub fn clock(mut self, clock_fq_hz: u32) -> Self {
+        self.config.clock_fq_hz = clock_fq_hz;
+        self
+    }
+
+    /// Reference input, CLKIN or crystal
+    pub fn clock_source(mut self, clock_source: ClockSource) -> Self {
+        self.config.clock_source = clock_source;
+        self
+    }
+
+    /// Automatic switch between the two reference inputs
+    pub fn input_failover(mut self, input_failover: InputFailover) -> Self {
+        self.config.input_failover = input_failover;
+        self
+    }
+
+    /// VCO frequency, Hz
+    pub fn vco(mut self, vco_fq_hz: u32) -> Self {
+        self.config.vco_fq_hz = vco_fq_hz;
+        self
+    }
+
+    /// Output 1 frequency, Hz
+    pub fn output1(mut self, output_fq_hz: u32) -> Self {
+        self.config.outputs[0] = Some(output_fq_hz);
+        self
+    }
+
+    /// Output 2 frequency, Hz
+    pub fn output2(mut self, output_fq_hz: u32) -> Self {
+        self.config.outputs[1] = Some(output_fq_hz);
+        self
+    }
+
+    /// Output 3 frequency, Hz
+    pub fn output3(mut self, output_fq_hz: u32) -> Self {
+        self.config.outputs[2] = Some(output_fq_hz);
+        self
+    }
+
+    /// Output 4 frequency, Hz
+    pub fn output4(mut self, output_fq_hz: u32) -> Self {
+        self.config.outputs[3] = Some(output_fq_hz);
+        self
+    }
+
+    /// Spread spectrum modulation of the outputs
+    pub fn spread_spectrum(mut self, spread_spectrum: SpreadSpectrum) -> Self {
+        self.config.spread_spectrum = spread_spectrum;
+        self
+    }
+
+    /// Manual VCO band, 0x00 - 0x1F; `None` leaves the band to the VCO calibration
+    pub fn vco_band(mut self, vco_band: Option<u8>) -> Self {
+        self.config.test_mode_vco_band = vco_band.is_some();
+        self.config.vco_band = vco_band.unwrap_or(0x0D);
+        self
+    }
+
+    /// Polarity of the SD/OE pin
+    pub fn oe_polarity(mut self, oe_polarity: OePolarity) -> Self {
+        self.config.oe_polarity = oe_polarity;
+        self
+    }
+
+    /// Sigma-delta modulator order of the feedback divider
+    pub fn sigma_delta_order(mut self, sigma_delta_order: SigmaDeltaOrder) -> Self {
+        self.config.sigma_delta_order = sigma_delta_order;
+        self
+    }
+
+    /// Calibration start bit of the configuration burst
+    pub fn calibration_trigger(mut self, calibration_trigger: CalibrationTrigger) -> Self {
+        self.config.calibration_trigger = calibration_trigger;
+        self
+    }
+
+    /// VCO monitor, needs a `variant` supporting it
+    pub fn vco_monitor(mut self, vco_monitor: bool) -> Self {
+        self.config.vco_monitor = vco_monitor;
+        self
+    }
+
+    /// Reference bypass output, OUT1 only, needs a `variant` supporting it
+    pub fn enable_ref_out(mut self, enable_ref_out: Option<OutputChannel>) -> Self {
+        self.config.enable_ref_out = enable_ref_out;
+        self
+    }
+
+    /// Format, drive strength, polarity, slew rate and enable of one output
+    pub fn clock_output(mut self, output: OutputChannel, clock_output: ClockOutputConfig) -> Self {
+        self.config.clock_outputs[output.index()] = clock_output;
+        self
+    }
+
+    /// Output enables and output voltage
+    pub fn global_output(mut self, global_output: GlobalOutputConfig) -> Self {
+        self.config.global_output = global_output;
+        self
+    }
+
+    /// Wait after `write_config`, ms, 0 to skip
+    pub fn stabilization_ms(mut self, stabilization_ms: u32) -> Self {
+        self.config.stabilization_ms = stabilization_ms;
+        self
+    }
+
+    /// Validate the frequency plan and return the configuration
+    pub fn build(self) -> Result<Pll5p49vConfig, ConfigError> {
+        let config = self.config;
+
+        if config.clock_fq_hz == 0 {
+            return Err(ConfigError::ClockZero);
+        }
+
+        let failover = config.input_failover;
+
+        if failover.enabled && failover.primary == failover.fallback {
+            return Err(ConfigError::FailoverSourcesEqual);
+        }
+
+        // With failover the frequency has to suit both inputs
+        let sources = [
+            Some(config.primary_source()),
+            failover.enabled.then_some(failover.fallback),
+        ];
+
+        for source in sources.into_iter().flatten() {
+            let (clock_min_hz, clock_max_hz) = source.frequency_range_hz();
+
+            if config.clock_fq_hz < clock_min_hz || config.clock_fq_hz > clock_max_hz {
+                return Err(ConfigError::ClockFrequencyOutOfRange {
+                    hz: config.clock_fq_hz,
+                    min: clock_min_hz,
+                    max: clock_max_hz,
+                });
+            }
+        }
+
+        let (vco_min_hz, vco_max_hz) = self.generation.vco_range_hz();
+
+        if config.vco_fq_hz < vco_min_hz || config.vco_fq_hz > vco_max_hz {
+            return Err(ConfigError::VcoOutOfRange);
+        }
+
+        // Without a known variant the features can not be vouched for
+        if config.vco_monitor && !self.variant.is_some_and(|v| v.supports_vco_monitor) {
+            return Err(ConfigError::FeatureUnsupportedOnVariant);
+        }
+
+        if config.enable_ref_out.is_some() && !self.variant.is_some_and(|v| v.supports_ref_out) {
+            return Err(ConfigError::FeatureUnsupportedOnVariant);
+        }
+
+        check_dividers(&config)?;
+
+        if !config.spread_spectrum.is_valid() {
+            return Err(ConfigError::SpreadDeviationOutOfRange);
+        }
+
+        if config.vco_band > 0x1F {
+            return Err(ConfigError::VcoBandOutOfRange);
+        }
+
+        Ok(config)
+    }
+}
+
+/// Check that the feedback and output dividers fit their register fields, and
+/// that no LVDS output runs at 1.8 V
+fn check_dividers(config: &Pll5p49vConfig) -> Result<(), ConfigError> {
+    if config.clock_fq_hz == 0 {
+        return Err(ConfigError::ClockZero);
+    }
+
+    let feedback_divider = compute_feedback_divider(config.vco_fq_hz, config.clock_fq_hz);
+
+    if feedback_divider >> 32 > 0xFFF {
+        return Err(ConfigError::FeedbackDividerOverflow);
+    }
+
+    // Zero integer part, the VCO is below the input clock
+    if feedback_divider >> 32 == 0 {
+        return Err(ConfigError::VcoOutOfRange);
+    }
+
+    if config.sigma_delta_order == SigmaDeltaOrder::Bypass && feedback_divider & 0xFFFFFFFF != 0 {
+        return Err(ConfigError::SigmaDeltaRequiredForFractional);
+    }
+
+    // SEL_EXT of OD2..OD4 cascades the previous divider instead
+    if let Some(ref_out) = config.enable_ref_out {
+        if ref_out != OutputChannel::Out1 {
+            return Err(ConfigError::RefOutUnavailable {
+                channel: ref_out.index(),
+            });
+        }
+    }
+
+    // Output divider integer part is a 12-bit field (registers 0x2D - 0x2E),
+    // zero integer part means the output is above VCO / 2
+    for (channel, output_fq_hz) in config.outputs.iter().enumerate() {
+        let Some(&output_fq_hz) = output_fq_hz.as_ref() else {
+            continue;
+        };
+
+        let out_of_range = ConfigError::OutputDividerOutOfRange {
+            channel,
+            requested_hz: output_fq_hz,
+        };
+
+        if output_fq_hz == 0 {
+            return Err(out_of_range);
+        }
+
+        let od = compute_output_divider(config.vco_fq_hz, output_fq_hz);
+
+        if od >> 32 == 0 || od >> 32 > 0xFFF {
+            return Err(out_of_range);
+        }
+    }
+
+    if config.global_output.voltage == OutputVoltage::V18 {
+        let lvds = config
+            .clock_outputs
+            .iter()
+            .position(|output| output.format == OutputFormat::Lvds);
+
+        if let Some(channel) = lvds {
+            return Err(ConfigError::LvdsAt1v8 { channel });
+        }
+    }
+
+    Ok(())
+}
+
+/// Feedback divider for the VCO and input clock frequencies, 32.32 fixed point
+///
+/// Usable in constants, e.g. to store precomputed dividers in flash. Panics
+/// (fails to compile in a constant) for a zero clock frequency.
+pub const fn compute_feedback_divider(vco_fq_hz: u32, clock_fq_hz: u32) -> u64 {
+    ((vco_fq_hz as u64) << 32) / (clock_fq_hz as u64)
+}
+
+/// Output divider for the VCO and output frequencies, 32.32 fixed point
+/// (the output is the VCO divided by twice this value)
+///
+/// Usable in constants like `compute_feedback_divider`, panics for a zero
+/// output frequency.
+pub const fn compute_output_divider(vco_fq_hz: u32, output_fq_hz: u32) -> u64 {
+    ((vco_fq_hz as u64) << 31) / (output_fq_hz as u64)
+}
+
+/// Output frequency produced by an output divider value (as packed by `write_config`)
+///
+/// Returns 0 for a zero divider.
+pub fn compute_actual_frequency(vco_fq_hz: u32, od: u64) -> u32 {
+    if od == 0 {
+        return 0;
+    }
+
+    (((vco_fq_hz as u64) << 31) / od) as u32
+}
+
+/// Signed deviation of the actual output frequency from the requested one, ppm
+pub fn frequency_error_ppm(requested: u32, vco_fq_hz: u32, od: u64) -> i32 {
+    (frequency_error_ppb(requested, vco_fq_hz, od) / 1000) as i32
+}
+
+/// Signed deviation of the actual output frequency from the requested one, ppb
+fn frequency_error_ppb(requested: u32, vco_fq_hz: u32, od: u64) -> i64 {
+    if requested == 0 || od == 0 {
+        return 0;
+    }
+
+    // Actual frequency in nano-Hz keeps the sub-Hz part of the error
+    let actual_nhz = ((((vco_fq_hz as u128) << 31) * 1000000000) / od as u128) as i128;
+
+    ((actual_nhz - requested as i128 * 1000000000) / requested as i128) as i64
+}
+
+/// Ethernet PHY reference clock, see `Pll5p49vConfig::for_ethernet`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EthernetPhyInterface {
+    /// RMII / MII reference, 25 MHz LVCMOS
+    Rmii25MHz,
+    /// RGMII / GMII reference, 125 MHz LVCMOS
+    Rgmii125MHz,
+    /// SGMII SerDes reference, 125 MHz LVDS: the 625 MHz line clock is above
+    /// the output range, the PHY multiplies the reference up to it
+    Sgmii125MHz,
+}
+
+impl EthernetPhyInterface {
+    /// Output frequency, format and drive strength
+    const fn output(self) -> (u32, OutputFormat, DriveStrength) {
+        match self {
+            EthernetPhyInterface::Rmii25MHz => (25000000, OutputFormat::Lvcmos, DriveStrength::Ma8),
+            EthernetPhyInterface::Rgmii125MHz => {
+                (125000000, OutputFormat::Lvcmos, DriveStrength::Ma16)
+            }
+            EthernetPhyInterface::Sgmii125MHz => {
+                (125000000, OutputFormat::Lvds, DriveStrength::DEFAULT)
+            }
+        }
+    }
+}
+
+impl Pll5p49vConfig {
+    /// Find the VCO frequency giving the smallest total squared error of the outputs
+    ///
+    /// Searches the 5th generation VCO range (supported by both generations) in
+    /// 1 MHz steps, skipping candidates whose dividers do not fit the registers.
+    /// Returns `VcoOutOfRange` if no candidate in the range is usable.
+    pub fn optimal_vco(clock_fq_hz: u32, outputs: [u32; 4]) -> Result<u32, ConfigError> {
+        search_vco(clock_fq_hz, &outputs)
+    }
+
+    /// Copy with each output moved to the nearest frequency an integer or
+    /// low-denominator output divider produces exactly, and the signed
+    /// deviation of the produced frequencies from the requested ones, ppb
+    ///
+    /// Output dividers are snapped to multiples of 1/256 giving a whole Hz
+    /// frequency from the configured VCO, so `write_config` of the adjusted
+    /// configuration programs that divider without rounding. The error also
+    /// includes the feedback divider rounding of the VCO. Disabled outputs
+    /// stay disabled with a zero error.
+    pub fn closest_achievable(&self) -> Result<(Pll5p49vConfig, [i64; 4]), ConfigError> {
+        // Output divider fraction denominator, a power of two so the
+        // fraction register holds it exactly
+        const MAX_DENOMINATOR: u64 = 256;
+
+        check_dividers(self)?;
+
+        let feedback_divider = compute_feedback_divider(self.vco_fq_hz, self.clock_fq_hz);
+        let actual_vco_hz = vco_frequency(feedback_divider & !0xFF, self.clock_fq_hz);
+
+        // Frequencies produced exactly by an OD of n / MAX_DENOMINATOR are the
+        // divisors of VCO * MAX_DENOMINATOR / 2, n being the quotient
+        let dividend = self.vco_fq_hz as u64 * MAX_DENOMINATOR / 2;
+        let numerator_range = MAX_DENOMINATOR..=(0xFFF * MAX_DENOMINATOR + MAX_DENOMINATOR - 1);
+
+        let exact = |candidate_hz: u64| {
+            candidate_hz != 0
+                && dividend.is_multiple_of(candidate_hz)
+                && numerator_range.contains(&(dividend / candidate_hz))
+        };
+
+        let mut adjusted = self.clone();
+        let mut error_ppb = [0i64; 4];
+
+        for (channel, output) in adjusted.outputs.iter_mut().enumerate() {
+            let Some(requested_hz) = *output else {
+                continue;
+            };
+
+            let requested = requested_hz as u64;
+
+            let snapped_hz = (0..=u32::MAX as u64)
+                .find_map(|distance| {
+                    let below = requested.checked_sub(distance).filter(|&hz| exact(hz));
+                    let above =
+                        Some(requested + distance).filter(|&hz| hz <= u32::MAX as u64 && exact(hz));
+
+                    below.or(above)
+                })
+                .ok_or(ConfigError::OutputDividerOutOfRange {
+                    channel,
+                    requested_hz,
+                })?;
+
+            let od = (dividend / snapped_hz) << (32 - MAX_DENOMINATOR.trailing_zeros());
+
+            *output = Some(snapped_hz as u32);
+            error_ppb[channel] = frequency_error_ppb(requested_hz, actual_vco_hz, od);
+        }
+
+        Ok((adjusted, error_ppb))
+    }
+
+    /// Configuration for the input clock and 1 - 4 output frequencies
+    ///
+    /// The VCO is chosen by `optimal_vco` over the given outputs, outputs
+    /// beyond the end of the slice are disabled. Other settings are the defaults.
+    pub fn auto(clock_fq_hz: u32, outputs: &[u32]) -> Result<Pll5p49vConfig, ConfigError> {
+        if outputs.is_empty() || outputs.len() > 4 {
+            return Err(ConfigError::OutputCountOutOfRange);
+        }
+
+        let vco_fq_hz = search_vco(clock_fq_hz, outputs)?;
+
+        let mut builder = Pll5p49vConfig::builder().clock(clock_fq_hz).vco(vco_fq_hz);
+
+        builder.config.outputs = [None; 4];
+
+        for (output, &output_fq_hz) in builder.config.outputs.iter_mut().zip(outputs) {
+            *output = Some(output_fq_hz);
+        }
+
+        builder.build()
+    }
+
+    /// USB 3.0 reference clock: 100 MHz HCSL on OUT1 with 5000 ppm down-spread
+    ///
+    /// The 5 GHz SuperSpeed bit clock is far above the output range of the
+    /// chip, the PHY multiplies this reference up to it. USB 3.0 allows a
+    /// spread of 0 to -5000 ppm at 30 - 33 kHz, `SS_MODULATION_HZ` is within
+    /// that. The VCO is chosen by `optimal_vco` for the `reference_hz` input
+    /// (typically 25 MHz, 100 MHz or 125 MHz), OUT2..OUT4 are disabled.
+    pub fn for_usb3(reference_hz: u32) -> Result<Pll5p49vConfig, ConfigError> {
+        const USB3_REFCLK_HZ: u32 = 100000000;
+
+        let vco_fq_hz = search_vco(reference_hz, &[USB3_REFCLK_HZ])?;
+
+        let mut builder = Pll5p49vConfig::builder()
+            .clock(reference_hz)
+            .vco(vco_fq_hz)
+            .spread_spectrum(SpreadSpectrum::DownSpread {
+                deviation_ppm: 5000,
+            })
+            .clock_output(
+                OutputChannel::Out1,
+                ClockOutputConfig {
+                    format: OutputFormat::Hcsl,
+                    ..ClockOutputConfig::DEFAULT
+                },
+            );
+
+        builder.config.outputs = [Some(USB3_REFCLK_HZ), None, None, None];
+
+        builder.build()
+    }
+
+    /// Ethernet PHY reference clock on OUT1, 3.3 V outputs, no spread spectrum
+    ///
+    /// Frequency, format and drive strength follow `phy_interface`: 8 mA for
+    /// the 25 MHz clock keeps the edges slower, 16 mA drives the 125 MHz one.
+    /// Ethernet references tolerate +/-50 ppm at most, so check the error of
+    /// e.g. a `PllFrequencyPlan` for uncommon `reference_hz` values.
+    /// OUT2..OUT4 are disabled.
+    pub fn for_ethernet(
+        reference_hz: u32,
+        phy_interface: EthernetPhyInterface,
+    ) -> Result<Pll5p49vConfig, ConfigError> {
+        let (output_fq_hz, format, drive_strength) = phy_interface.output();
+
+        let vco_fq_hz = search_vco(reference_hz, &[output_fq_hz])?;
+
+        let mut builder = Pll5p49vConfig::builder()
+            .clock(reference_hz)
+            .vco(vco_fq_hz)
+            .spread_spectrum(SpreadSpectrum::Disabled)
+            .clock_output(
+                OutputChannel::Out1,
+                ClockOutputConfig {
+                    format,
+                    drive_strength,
+                    ..ClockOutputConfig::DEFAULT
+                },
+            )
+            .global_output(GlobalOutputConfig {
+                voltage: OutputVoltage::V33,
+            });
+
+        builder.config.outputs = [Some(output_fq_hz), None, None, None];
+
+        builder.build()
+    }
+}
+
+/// VCO search behind `optimal_vco` and `auto`
+fn search_vco(clock_fq_hz: u32, outputs: &[u32]) -> Result<u32, ConfigError> {
+    const VCO_STEP_HZ: u32 = 1000000;
+
+    if clock_fq_hz == 0 {
+        return Err(ConfigError::ClockZero);
+    }
+
+    if let Some(channel) = outputs.iter().position(|&output_fq_hz| output_fq_hz == 0) {
+        return Err(ConfigError::OutputDividerOutOfRange {
+            channel,
+            requested_hz: 0,
+        });
+    }
+
+    let (vco_min_hz, vco_max_hz) = Generation::Fifth.vco_range_hz();
+
+    let mut best: Option<(u32, u128)> = None;
+
+    for vco_fq_hz in (vco_min_hz..=vco_max_hz).step_by(VCO_STEP_HZ as usize) {
+        let feedback_divider = compute_feedback_divider(vco_fq_hz, clock_fq_hz);
+
+        if feedback_divider >> 32 > 0xFFF {
+            continue;
+        }
+
+        let mut total_error = 0u128;
+        let mut usable = true;
+
+        for &output_fq_hz in outputs.iter() {
+            let od = compute_output_divider(vco_fq_hz, output_fq_hz);
+
+            if od >> 32 == 0 || od >> 32 > 0xFFF {
+                usable = false;
+                break;
+            }
+
+            let error = frequency_error_ppb(output_fq_hz, vco_fq_hz, od) as i128;
+            total_error += (error * error) as u128;
+        }
+
+        if usable && best.is_none_or(|(_, best_error)| total_error < best_error) {
+            best = Some((vco_fq_hz, total_error));
+        }
+    }
+
+    best.map(|(vco_fq_hz, _)| vco_fq_hz)
+        .ok_or(ConfigError::VcoOutOfRange)
+}
+
+/// Validated configuration with the output frequencies the chip will produce
+#[derive(Clone, Debug)]
+pub struct PllFrequencyPlan {
+    /// Configuration the plan is computed for
+    pub config: Pll5p49vConfig,
+    /// Output 1..4 frequencies produced by the programmed dividers, Hz
+    pub actual_output_hz: [u32; 4],
+    /// Output 1..4 deviation from the requested frequencies, ppm
+    pub error_ppm: [i32; 4],
+}
+
+impl PllFrequencyPlan {
+    /// Compute the actual frequencies from the dividers as stored in the chip
+    ///
+    /// The dividers lose the lowest fraction byte, as in `write_config`.
+    /// Spread spectrum is not taken into account. `config` is expected to come
+    /// from `Pll5p49vConfigBuilder::build`, zero frequencies and disabled
+    /// outputs give zero results.
+    pub fn from_config(config: Pll5p49vConfig) -> Self {
+        let vco_fq_hz = if config.clock_fq_hz == 0 {
+            0
+        } else {
+            let feedback_divider = compute_feedback_divider(config.vco_fq_hz, config.clock_fq_hz);
+
+            vco_frequency(feedback_divider & !0xFF, config.clock_fq_hz)
+        };
+
+        let mut actual_output_hz = [0u32; 4];
+        let mut error_ppm = [0i32; 4];
+
+        for (output, &output_fq_hz) in config.outputs.iter().enumerate() {
+            let Some(output_fq_hz) = output_fq_hz.filter(|&output_fq_hz| output_fq_hz != 0) else {
+                continue;
+            };
+
+            let od = compute_output_divider(config.vco_fq_hz, output_fq_hz) & !0xFF;
+
+            actual_output_hz[output] = compute_actual_frequency(vco_fq_hz, od);
+            error_ppm[output] = frequency_error_ppm(output_fq_hz, vco_fq_hz, od);
+        }
+
+        PllFrequencyPlan {
+            config,
+            actual_output_hz,
+            error_ppm,
+        }
+    }
+}
+
+/// Table of requested vs. actual frequency and error per output
+#[cfg(feature = "defmt")]
+impl defmt::Format for PllFrequencyPlan {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "output requested_hz actual_hz error_ppm");
+
+        for output in 0..4 {
+            defmt::write!(
+                f,
+                "\nOUT{} {} {} {}",
+                output + 1,
+                self.config.outputs[output],
+                self.actual_output_hz[output],
+                self.error_ppm[output]
+            );
+        }
+    }
+}
+
+/// Clock output channel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OutputChannel {
+    Out1,
+    Out2,
+    Out3,
+    Out4,
+}
+
+impl OutputChannel {
+    /// All outputs, in order
+    pub const ALL: [OutputChannel; 4] = [
+        OutputChannel::Out1,
+        OutputChannel::Out2,
+        OutputChannel::Out3,
+        OutputChannel::Out4,
+    ];
+
+    /// Iterate over all outputs, in order
+    pub fn iter() -> impl Iterator<Item = OutputChannel> {
+        Self::ALL.into_iter()
+    }
+
+    /// Channel index, 0..3
+    pub const fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Output enable bit in register 0x68 (bit 6 - OUT1 .. bit 3 - OUT4)
+    const fn enable_mask(self) -> u8 {
+        regs::OUTPUT_EN_OUT1 >> self.index()
+    }
+
+    /// Output divider control register (0x21, 0x31, 0x41, 0x51)
+    const fn divider_control_reg(self) -> u8 {
+        regs::OD1_CONTROL + regs::OD_STRIDE * self.index() as u8
+    }
+
+    /// First register of the output configuration pair (0x60, 0x62, 0x64, 0x66)
+    const fn config_reg(self) -> u8 {
+        regs::CLK1_CFG_0 + regs::CLK_CFG_STRIDE * self.index() as u8
+    }
+}
+
+/// Output signal format, bits 7:5 of the first output configuration register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum OutputFormat {
+    Lvpecl = 0b000,
+    Lvcmos = 0b001,
+    Hcsl = 0b010,
+    Lvds = 0b011,
+}
+
+impl OutputFormat {
+    /// Mask of the format bits
+    const MASK: u8 = 0xE0;
+
+    /// Format bits as placed in the register
+    const fn bits(self) -> u8 {
+        (self as u8) << 5
+    }
+
+    /// Decode the format from the first output configuration register
+    const fn from_reg(value: u8) -> Option<Self> {
+        match (value & Self::MASK) >> 5 {
+            0b000 => Some(OutputFormat::Lvpecl),
+            0b001 => Some(OutputFormat::Lvcmos),
+            0b010 => Some(OutputFormat::Hcsl),
+            0b011 => Some(OutputFormat::Lvds),
+            _ => None,
+        }
+    }
+}
+
+/// Output slew rate, bits 1:0 of the first output configuration register
+///
+/// LVCMOS uses both bits, LVDS only bit 1 (fast or slow), other formats have
+/// no slew rate selection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SlewRate {
+    Fast,
+    Medium,
+    Slow,
+}
+
+impl SlewRate {
+    /// Mask and bits of the slew rate field for the given format
+    const fn field(self, format: OutputFormat) -> Option<(u8, u8)> {
+        match (format, self) {
+            (OutputFormat::Lvcmos, SlewRate::Fast) => Some((0x03, 0x03)),
+            (OutputFormat::Lvcmos, SlewRate::Medium) => Some((0x03, 0x01)),
+            (OutputFormat::Lvcmos, SlewRate::Slow) => Some((0x03, 0x00)),
+            (OutputFormat::Lvds, SlewRate::Fast) => Some((0x02, 0x02)),
+            (OutputFormat::Lvds, SlewRate::Slow) => Some((0x02, 0x00)),
+            _ => None,
+        }
+    }
+
+    /// Decode the slew rate from the first output configuration register,
+    /// `Fast` for formats without a slew rate selection
+    const fn from_reg(value: u8, format: OutputFormat) -> Self {
+        match (format, value & 0x03) {
+            (OutputFormat::Lvcmos, 0x03) => SlewRate::Fast,
+            (OutputFormat::Lvcmos, 0x00) => SlewRate::Slow,
+            (OutputFormat::Lvcmos, _) => SlewRate::Medium,
+            (OutputFormat::Lvds, bits) if bits & 0x02 == 0 => SlewRate::Slow,
+            _ => SlewRate::Fast,
+        }
+    }
+}
+
+/// Clock output polarity, bit 0 of the second output configuration register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Polarity {
+    Normal,
+    Inverted,
+}
+
+/// Level of the SD/OE pin that enables the outputs, SP bit of register 0x10
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OePolarity {
+    /// Outputs enabled with the pin high, the power-up default
+    ActiveHigh,
+    /// Outputs enabled with the pin low
+    ActiveLow,
+}
+
+/// Output clock source, bits of the output divider control register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OutputMode {
+    /// Output divider fed by the VCO (EN_FOD set)
+    Pll,
+    /// Reference clock routed to the output, available on OUT1 only (SEL_EXT set)
+    Bypass,
+}
+
+impl OutputMode {
+    /// Mask of the SEL_EXT (bit 2) and EN_FOD (bit 0) bits
+    const MASK: u8 = 0x05;
+
+    /// Source selection bits as placed in the register
+    const fn bits(self) -> u8 {
+        match self {
+            OutputMode::Pll => 0x01,
+            OutputMode::Bypass => 0x04,
+        }
+    }
+}
+
+/// Signal source of an output pin, see `configure_output_mux`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum DividerSource {
+    /// Output divider 1
+    Od1,
+    /// Output divider 2
+    Od2,
+    /// Output divider 3
+    Od3,
+    /// Output divider 4
+    Od4,
+    /// Reference clock, bypassing the PLL
+    Bypass,
+}
+
+impl DividerSource {
+    /// Output mode selecting this source on `output`, if the chip can route it
+    ///
+    /// Every output pin is wired to the divider of the same number, the only
+    /// other choice is the reference bypass of OUT1.
+    const fn mode_for(self, output: OutputChannel) -> Option<OutputMode> {
+        match (self, output) {
+            (DividerSource::Od1, OutputChannel::Out1)
+            | (DividerSource::Od2, OutputChannel::Out2)
+            | (DividerSource::Od3, OutputChannel::Out3)
+            | (DividerSource::Od4, OutputChannel::Out4) => Some(OutputMode::Pll),
+            (DividerSource::Bypass, OutputChannel::Out1) => Some(OutputMode::Bypass),
+            _ => None,
+        }
+    }
+}
+
+/// LVCMOS output drive strength, bits 4:3 of the first output configuration register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[repr(u8)]
+pub enum DriveStrength {
+    Ma8 = 0b00,
+    Ma12 = 0b01,
+    Ma16 = 0b10,
+    Ma24 = 0b11,
+}
+
+impl DriveStrength {
+    /// 24 mA, the power-up default
+    pub const DEFAULT: DriveStrength = DriveStrength::Ma24;
+
+    /// Mask of the drive strength bits
+    const MASK: u8 = 0x18;
+
+    /// Drive strength bits as placed in the register
+    const fn bits(self) -> u8 {
+        (self as u8) << 3
+    }
+
+    /// Decode the drive strength from the first output configuration register
+    const fn from_reg(value: u8) -> Self {
+        match (value & Self::MASK) >> 3 {
+            0b00 => DriveStrength::Ma8,
+            0b01 => DriveStrength::Ma12,
+            0b10 => DriveStrength::Ma16,
+            _ => DriveStrength::Ma24,
+        }
+    }
+}
+
+impl Default for DriveStrength {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Settings of one clock output, see `Pll5p49vConfig::clock_outputs` and
+/// `configure_all_outputs`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ClockOutputConfig {
+    /// Signal format
+    pub format: OutputFormat,
+    /// Drive strength, meaningful for LVCMOS only
+    pub drive_strength: DriveStrength,
+    /// Output polarity
+    pub polarity: Polarity,
+    /// Slew rate, ignored where the format has no such setting (see `SlewRate`)
+    pub slew_rate: SlewRate,
+    /// Output enable (register 0x68), in a `Pll5p49vConfig` an output without
+    /// a frequency stays disabled unless it is the reference bypass
+    pub enabled: bool,
+}
+
+impl ClockOutputConfig {
+    /// LVCMOS, 24 mA, normal polarity, fast slew rate, enabled
+    pub const DEFAULT: ClockOutputConfig = ClockOutputConfig {
+        format: OutputFormat::Lvcmos,
+        drive_strength: DriveStrength::DEFAULT,
+        polarity: Polarity::Normal,
+        slew_rate: SlewRate::Fast,
+        enabled: true,
+    };
+}
+
+impl Default for ClockOutputConfig {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Live state of a clock output, see `read_output_status`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct OutputStatus {
+    /// Output enable bit (register 0x68)
+    pub enabled: bool,
+    /// Signal format
+    pub format: OutputFormat,
+    /// Drive strength, meaningful for LVCMOS only
+    pub drive_strength: DriveStrength,
+    /// Output divider integer part, 12 bits
+    pub divider_integer: u32,
+    /// Output divider fractional part in units of 2^-32, as `OutputDividerRegs`
+    pub divider_fractional: u32,
+}
+
+/// Chip state for bug reports, see `read_diagnostics`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PllDiagnostics {
+    /// Configuration registers 0x00 - 0x69
+    pub register_snapshot: [u8; CONFIG_REGISTER_COUNT],
+    /// VCO band index of the last calibration (register 0x99, bits 7:3)
+    pub vco_index: u8,
+}
+
+/// One line of `key=value` pairs, registers as hex bytes from 0x00
+#[cfg(feature = "defmt")]
+impl defmt::Format for PllDiagnostics {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PLL_DIAG vco_index={=u8} regs={=[u8]:02x}",
+            self.vco_index,
+            self.register_snapshot
+        );
+    }
+}
+
+/// Driver errors
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Pll5p49vError<E> {
+    /// I2C bus error
+    I2c(E),
+    /// Operation is not valid for the current output mode (e.g. output format)
+    InvalidModeForOperation,
+    /// Setting is not available for the current output format
+    NotApplicableForFormat,
+    /// Chip does not respond or responds with invalid data
+    HardwareNotResponding,
+    /// Configuration can not be programmed
+    Config(ConfigError),
+    /// Crystal load capacitance outside of 8 - 20 pF
+    LoadCapOutOfRange,
+    /// Register content differs from the value computed from the configuration
+    /// (see `verify_config`)
+    Mismatch { reg: u8, expected: u8, actual: u8 },
+    /// Register 0x00 reads as a floating bus (see `read_device_id`)
+    UnexpectedDeviceId { got: u8 },
+    /// Setting the OE pin failed (see `assert_oe`)
+    OePin,
+    /// VCO index kept changing after the calibration (see `calibrate_vco_with_retries`)
+    CalibrationUnstable { retries: u8, last_index: u8 },
+    /// No ACK at the I2C address (see `new_probed`)
+    DeviceNotFound,
+}
+
+/// Calibration repetitions of `calibrate_vco` while the VCO index is unstable
+pub const DEFAULT_CALIBRATION_RETRIES: u8 = 3;
+
+/// Timing of the VCO calibration pulse (0-1-0 on register 0x1C, bit 7)
+///
+/// Longer waits help on boards with slow buses or long traces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PllCalibrationParams {
+    /// Wait after clearing the calibration bit, us
+    pub reset_assert_us: u32,
+    /// Time the calibration bit is held set, us
+    pub reset_hold_us: u32,
+    /// Wait after clearing the bit again before the VCO index is read, ms
+    pub post_cal_settle_ms: u32,
+}
+
+impl PllCalibrationParams {
+    /// 1 ms for every step, the timing of `calibrate_vco`
+    pub const DEFAULT: PllCalibrationParams = PllCalibrationParams {
+        reset_assert_us: 1000,
+        reset_hold_us: 1000,
+        post_cal_settle_ms: 1,
+    };
+}
+
+impl Default for PllCalibrationParams {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// A blanket `From<E>` would overlap with `From<T> for T`, bus errors are
+// converted with `map_err(Pll5p49vError::I2c)` at the I2C calls instead
+impl<E> From<ConfigError> for Pll5p49vError<E> {
+    fn from(e: ConfigError) -> Self {
+        Pll5p49vError::Config(e)
+    }
+}
+
+/// I2C address of the PLL chip, selected by the ADDR pin level latched at power-up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Address {
+    /// ADDR pin low (or not connected), 0x6A
+    Default,
+    /// ADDR pin high, 0x6B
+    Alt,
+}
+
+impl Address {
+    /// 7-bit I2C address
+    pub const fn addr(self) -> u8 {
+        match self {
+            Address::Default => 0x6A,
+            Address::Alt => 0x6B,
+        }
+    }
+}
+
+/// Probe both chip addresses, e.g. to find out how the ADDR pin is wired
+///
+/// Each slot is `Some` if the address acknowledged. The probe is a one byte
+/// read instead of a zero-length write, which not every HAL supports (e.g.
+/// embassy-rp refuses an empty write buffer). Errors other than a missing
+/// acknowledge are returned.
+pub async fn scan<I2C, E>(i2c: &mut I2C) -> Result<[Option<Address>; 2], Pll5p49vError<E>>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    E: embedded_hal_async::i2c::Error,
+{
+    let mut found = [None; 2];
+
+    for (slot, address) in found.iter_mut().zip([Address::Default, Address::Alt]) {
+        let mut resp_buff = [0u8; 1];
+
+        match i2c.read(address.addr(), &mut resp_buff).await {
+            Ok(()) => *slot = Some(address),
+            Err(e) => match e.kind() {
+                embedded_hal_async::i2c::ErrorKind::NoAcknowledge(_) => {}
+                _ => return Err(Pll5p49vError::I2c(e)),
+            },
+        }
+    }
+
+    Ok(found)
+}
+
+/// Write the configurations of several chips sharing a bus, in slice order
+///
+/// Stops at the first failure and returns the address of that chip, chips
+/// before it are programmed. VCO calibration of 6th generation chips is left
+/// to the caller (see `Pll5p49v::calibrate_vco`). Each chip gets the same
+/// registers as from a first `Pll5p49v::write_config`, the dividers of
+/// disabled outputs are left untouched.
+pub async fn write_config_multi<I2C, E>(
+    i2c: &mut I2C,
+    configs: &[(Address, Pll5p49vConfig)],
+) -> Result<(), (Address, Pll5p49vError<E>)>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    for (address, config) in configs {
+        let prog_array =
+            build_prog_array(config).map_err(|e| (*address, Pll5p49vError::Config(e)))?;
+
+        // prog_array starts with the register address
+        let values = &prog_array[1..];
+        let skip = unwritten_registers(config);
+
+        let mut from = 0;
+
+        while let Some((start, end)) = RegisterCache::EMPTY.next_changed_run(values, &skip, from) {
+            let mut burst = [0u8; PROG_ARRAY_SIZE];
+            burst[0] = start as u8;
+            burst[1..=end - start].copy_from_slice(&values[start..end]);
+
+            i2c.write(address.addr(), &burst[..=end - start])
+                .await
+                .map_err(|e| (*address, Pll5p49vError::I2c(e)))?;
+
+            from = end;
+        }
+    }
+
+    Ok(())
+}
+
+/// Output divider operation, INT_MODE bit of the output divider control register
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum OutputDividerMode {
+    /// Fractional divider
+    Fractional,
+    /// Integer-only divider, the fraction registers are zero
+    Integer,
+}
+
+impl OutputDividerMode {
+    /// Integer mode for a divider without a fraction, unless spread spectrum
+    /// modulates it
+    pub const fn detect(od: u64, spread: SpreadSpectrum) -> OutputDividerMode {
+        if od & 0xFFFFFFFF == 0 && matches!(spread, SpreadSpectrum::Disabled) {
+            OutputDividerMode::Integer
+        } else {
+            OutputDividerMode::Fractional
+        }
+    }
+
+    const fn control_bits(self) -> u8 {
+        match self {
+            OutputDividerMode::Fractional => 0,
+            OutputDividerMode::Integer => regs::OD_CONTROL_INT_MODE,
+        }
+    }
+}
+
+/// Number of registers readable in a single burst, 0x00 - 0x99
+pub const REGISTER_MAP_SIZE: usize = regs::VCO_STATUS as usize + 1;
+
+/// Print all non-zero registers of a register dump
+#[cfg(feature = "defmt")]
+pub fn format_register_dump(dump: &[u8; REGISTER_MAP_SIZE]) {
+    for (reg, &value) in dump.iter().enumerate() {
+        if value != 0 {
+            defmt::debug!("reg 0x{:02X}: 0x{:02X}", reg, value);
+        }
+    }
+}
+
+/// Size of the configuration burst: start address and registers 0x00 - 0x69
+pub(crate) const PROG_ARRAY_SIZE: usize = 107;
+
+// The burst must end at the last configuration register
+const _: () = assert!(PROG_ARRAY_SIZE == regs::OUTPUT_CTRL as usize + 2);
+
+/// Configuration registers 0x00 - 0x69 by register number
+///
+/// Named setters own the bit layout of every field, other bits of the
+/// register are preserved. Starts from `DEFAULTS`, the factory reserved
+/// values with all programmable fields zero.
+struct RegisterBank([u8; CONFIG_REGISTER_COUNT]);
+
+impl RegisterBank {
+    const DEFAULTS: Self = {
+        let mut regs = [0u8; CONFIG_REGISTER_COUNT];
+
+        // Registers up to the feedback divider, a missing value fails to compile
+        let reserved: [u8; regs::FEEDBACK_DIV_INT_HI as usize] = [
+            0x61, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 0x00 - 0x07
+            0x00, 0xFF, 0x01, 0xC0, 0x00, 0xB6, 0xB4, 0x92, // 0x08 - 0x0F
+            0x00, 0x00, 0x81, 0x80, 0x00, 0x03, 0x84, // 0x10 - 0x16
+        ];
+
+        let mut reg = 0;
+        while reg < reserved.len() {
+            regs[reg] = reserved[reg];
+            reg += 1;
+        }
+
+        regs[regs::CAL_START as usize] = 0x1F;
+        regs[regs::VCO_MONITOR as usize] = 0xFD;
+        regs[regs::RC_CONTROL_0 as usize] = 0xC8;
+        regs[regs::RC_CONTROL_1 as usize] = 0x80;
+
+        let mut channel = 0;
+        while channel < 4 {
+            let offset = channel * regs::OD_STRIDE as usize;
+            regs[regs::OD1_CONTROL as usize + offset] =
+                regs::OD_CONTROL_RESET | regs::OD_CONTROL_EN_FOD;
+            regs[regs::OD1_SS_PERIOD_1 as usize + offset] = 0x04;
+
+            let offset = channel * regs::CLK_CFG_STRIDE as usize;
+            regs[regs::CLK1_CFG_0 as usize + offset] = 0x3B;
+            regs[regs::CLK1_CFG_1 as usize + offset] = regs::CLK_CFG_1_NORMAL_POLARITY;
+            channel += 1;
+        }
+
+        RegisterBank(regs)
+    };
+
+    /// Replace the bits selected by `mask` in register `reg`
+    fn set_bits(&mut self, reg: u8, mask: u8, value: u8) {
+        let reg = &mut self.0[reg as usize];
+        *reg = (*reg & !mask) | (value & mask);
+    }
+
+    fn set_flag(&mut self, reg: u8, bit: u8, set: bool) {
+        self.set_bits(reg, bit, if set { bit } else { 0 });
+    }
+
+    /// Register of output divider `channel` (0 - 3), given by its OD1 address
+    const fn od_reg(channel: usize, od1_reg: u8) -> u8 {
+        od1_reg + channel as u8 * regs::OD_STRIDE
+    }
+
+    /// EN_XTAL and EN_CLKIN (0x10), PRIMSRC (0x13)
+    fn set_input(&mut self, en_xtal: bool, en_clkin: bool, primsrc: bool) {
+        self.set_flag(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_XTAL,
+            en_xtal,
+        );
+        self.set_flag(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_CLKIN,
+            en_clkin,
+        );
+        self.set_flag(
+            regs::XTAL_X2_LOAD_CAP,
+            regs::XTAL_X2_LOAD_CAP_PRIMSRC,
+            primsrc,
+        );
+    }
+
+    /// SP bit (0x10)
+    fn set_oe_polarity(&mut self, oe_polarity: OePolarity) {
+        self.set_flag(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_SP,
+            oe_polarity == OePolarity::ActiveLow,
+        );
+    }
+
+    /// SD/OE pin function (0x10), set for shutdown
+    fn set_global_shutdown(&mut self, shutdown: bool) {
+        self.set_flag(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN,
+            shutdown,
+        );
+    }
+
+    /// VCO band test mode and band (0x11)
+    fn set_vco_band(&mut self, test_mode: bool, band: u8) {
+        self.set_flag(regs::VCO_BAND, regs::VCO_BAND_TEST_MODE, test_mode);
+        self.set_bits(regs::VCO_BAND, regs::VCO_BAND_MASK, band);
+    }
+
+    /// Feedback divider integer, 12 bits (0x17, 0x18 bits 7:4)
+    fn set_feedback_divider_int(&mut self, val: u32) {
+        self.0[regs::FEEDBACK_DIV_INT_HI as usize] = (val >> 4) as u8;
+        self.set_bits(regs::FEEDBACK_DIV_INT_LO, 0xF0, (val << 4) as u8);
+    }
+
+    /// Sigma-delta modulator order (0x18 bits 3:2), see `SigmaDeltaOrder::bits`
+    fn set_sigma_delta_order(&mut self, order: u8) {
+        self.set_bits(regs::FEEDBACK_DIV_INT_LO, 0x0C, order << 2);
+    }
+
+    /// Feedback divider fraction in units of 2^-32, bits 31:8 (0x19 - 0x1B)
+    ///
+    /// Bits 7:0 of the fraction have no register.
+    fn set_feedback_divider_frac(&mut self, val: u32) {
+        self.0[regs::FEEDBACK_DIV_FRAC_0 as usize] = (val >> 24) as u8;
+        self.0[regs::FEEDBACK_DIV_FRAC_1 as usize] = (val >> 16) as u8;
+        self.0[regs::FEEDBACK_DIV_FRAC_2 as usize] = (val >> 8) as u8;
+    }
+
+    /// Calibration start bit (0x1C)
+    fn set_calibration_start(&mut self, start: bool) {
+        self.set_flag(regs::CAL_START, regs::CAL_START_BIT, start);
+    }
+
+    /// VCO monitor enable (0x1D)
+    fn set_vco_monitor(&mut self, enabled: bool) {
+        self.set_flag(regs::VCO_MONITOR, regs::VCO_MONITOR_EN, enabled);
+    }
+
+    /// INT_MODE bit of the output divider control register (0xX1)
+    fn set_od_mode(&mut self, channel: usize, mode: OutputDividerMode) {
+        let reg = Self::od_reg(channel, regs::OD1_CONTROL);
+        self.set_bits(reg, regs::OD_CONTROL_INT_MODE, mode.control_bits());
+    }
+
+    /// SEL_EXT and EN_FOD bits of the output divider control register (0xX1)
+    fn set_od_source(&mut self, channel: usize, mode: OutputMode) {
+        let reg = Self::od_reg(channel, regs::OD1_CONTROL);
+        self.set_bits(reg, OutputMode::MASK, mode.bits());
+    }
+
+    /// Output divider fraction in units of 2^-32, bits 31:2 (0xX2 - 0xX5)
+    fn set_od_fraction(&mut self, channel: usize, val: u32) {
+        let reg = |od1_reg| Self::od_reg(channel, od1_reg);
+
+        self.set_bits(reg(regs::OD1_FRAC_0), 0b11, (val >> 30) as u8);
+        self.0[reg(regs::OD1_FRAC_1) as usize] = (val >> 22) as u8;
+        self.0[reg(regs::OD1_FRAC_2) as usize] = (val >> 14) as u8;
+        self.set_bits(reg(regs::OD1_FRAC_3), 0b11111100, (val >> 6) as u8);
+    }
+
+    /// Spread enable (0xX5), 24-bit step (0xX6 - 0xX8) and 13-bit period
+    /// (0xX9 - 0xXA) of an output divider
+    fn set_od_spread(&mut self, channel: usize, enabled: bool, step: u32, period: u16) {
+        let reg = |od1_reg| Self::od_reg(channel, od1_reg);
+
+        self.set_flag(reg(regs::OD1_FRAC_3), regs::OD_FRAC_3_SPREAD_EN, enabled);
+        self.0[reg(regs::OD1_SS_STEP_0) as usize] = (step >> 16) as u8;
+        self.0[reg(regs::OD1_SS_STEP_1) as usize] = (step >> 8) as u8;
+        self.0[reg(regs::OD1_SS_STEP_2) as usize] = step as u8;
+        self.0[reg(regs::OD1_SS_PERIOD_0) as usize] = (period >> 5) as u8;
+        self.set_bits(reg(regs::OD1_SS_PERIOD_1), 0xF8, (period << 3) as u8);
+    }
+
+    /// Output divider integer, 12 bits (0xXD, 0xXE bits 7:4)
+    fn set_od_integer(&mut self, channel: usize, val: u32) {
+        let reg = |od1_reg| Self::od_reg(channel, od1_reg);
+
+        self.0[reg(regs::OD1_INT_HI) as usize] = (val >> 4) as u8;
+        self.set_bits(reg(regs::OD1_INT_LO), 0xF0, (val << 4) as u8);
+    }
+
+    /// Output divider as 32.32 fixed point, integer and fraction
+    fn set_od(&mut self, channel: usize, od: u64) {
+        self.set_od_integer(channel, (od >> 32) as u32);
+        self.set_od_fraction(channel, od as u32);
+    }
+
+    /// Output configuration of output `channel` (0x60 - 0x67), the enable
+    /// bit is part of `set_global_output`
+    fn set_clock_output(&mut self, channel: usize, clock_output: &ClockOutputConfig) {
+        let reg = regs::CLK1_CFG_0 + channel as u8 * regs::CLK_CFG_STRIDE;
+        let format = clock_output.format;
+
+        self.set_bits(reg, OutputFormat::MASK, format.bits());
+        self.set_bits(reg, DriveStrength::MASK, clock_output.drive_strength.bits());
+
+        if let Some((mask, bits)) = clock_output.slew_rate.field(format) {
+            self.set_bits(reg, mask, bits);
+        }
+
+        self.set_flag(
+            reg + 1,
+            regs::CLK_CFG_1_NORMAL_POLARITY,
+            clock_output.polarity == Polarity::Normal,
+        );
+    }
+
+    /// Output enables (0x68)
+    fn set_output_enable(&mut self, output_en: u8) {
+        self.0[regs::OUTPUT_EN as usize] = output_en;
+    }
+
+    /// Output voltage (0x69)
+    fn set_global_output(&mut self, global_output: GlobalOutputConfig) {
+        self.0[regs::OUTPUT_CTRL as usize] = global_output.output_ctrl_reg();
+    }
+
+    /// Divider block of output `channel`, registers 0xX2 - 0xXE
+    fn od_block(&self, channel: usize) -> [u8; 13] {
+        let first = Self::od_reg(channel, regs::OD1_FRAC_0) as usize;
+
+        let mut block = [0u8; 13];
+        block.copy_from_slice(&self.0[first..first + 13]);
+        block
+    }
+
+    /// Configuration burst, the start address followed by the registers
+    fn to_prog_array(&self) -> [u8; PROG_ARRAY_SIZE] {
+        let mut prog_array = [0u8; PROG_ARRAY_SIZE];
+        prog_array[0] = regs::START;
+        prog_array[1..].copy_from_slice(&self.0);
+        prog_array
+    }
+}
+
+/// Build the configuration burst written by `write_config`
+///
+/// Pure function without I/O: index 0 is the start address, index `reg + 1`
+/// the value of register `reg`, e.g. the feedback divider at 24 - 28 (0x17 - 0x1B).
+/// Dividers that do not fit their register fields are refused instead of truncated.
+pub(crate) fn build_prog_array(
+    config: &Pll5p49vConfig,
+) -> Result<[u8; PROG_ARRAY_SIZE], ConfigError> {
+    check_dividers(config)?;
+
+    let vco_fq_hz = config.vco_fq_hz;
+
+    let mut bank = RegisterBank::DEFAULTS;
+
+    const EN_GLOBAL_SHUTDOWN: bool = false; // default: false
+    bank.set_global_shutdown(EN_GLOBAL_SHUTDOWN);
+    bank.set_oe_polarity(config.oe_polarity); // default: active high
+
+    let (en_xtal, en_clkin, primsrc) = config.input_bits(); // default: false, true, true
+    bank.set_input(en_xtal, en_clkin, primsrc);
+
+    bank.set_vco_band(config.test_mode_vco_band, config.vco_band); // default: false, 0x0D
+
+    // Calculate feedback divider, integer (upper 32 bits) and fractional (lower 32 bits)
+    let feedback_divider = compute_feedback_divider(vco_fq_hz, config.clock_fq_hz);
+
+    bank.set_feedback_divider_int((feedback_divider >> 32) as u32);
+    bank.set_feedback_divider_frac(feedback_divider as u32);
+    bank.set_sigma_delta_order(config.sigma_delta_order.bits(feedback_divider)); // 0=off, 1..3=order
+
+    bank.set_calibration_start(config.calibration_trigger.cal_start()); // default: true
+    bank.set_vco_monitor(config.vco_monitor); // default: false - looks like does not work for 5p49v6965
+
+    // Calculate output dividers, integer (upper 32 bits) and fractional (lower 32 bits).
+    // Spread spectrum is applied by the output dividers
+
+    let spread = config.spread_spectrum;
+
+    for (channel, output) in config.outputs.into_iter().enumerate() {
+        // Disabled outputs get zero placeholders, `write_config` does not write them
+        let output_fq_hz = output.unwrap_or(0);
+        let od = match output {
+            Some(output_fq_hz) => compute_output_divider(vco_fq_hz, output_fq_hz),
+            None => 0,
+        };
+
+        let (od, ss_step, ss_period) = spread.divider_params(od, output_fq_hz);
+
+        // Integer dividers run without the fractional logic, lower phase noise
+        bank.set_od_mode(channel, OutputDividerMode::detect(od, spread));
+        bank.set_od(channel, od);
+        bank.set_od_spread(
+            channel,
+            spread != SpreadSpectrum::Disabled,
+            ss_step,
+            ss_period,
+        );
+    }
+
+    if let Some(ref_out) = config.enable_ref_out {
+        bank.set_od_source(ref_out.index(), OutputMode::Bypass);
+    }
+
+    for (channel, clock_output) in config.clock_outputs.iter().enumerate() {
+        bank.set_clock_output(channel, clock_output);
+    }
+
+    bank.set_output_enable(config.output_enable_reg());
+    bank.set_global_output(config.global_output);
+
+    Ok(bank.to_prog_array())
+}
+
+/// Debug output of the driver, all methods default to doing nothing
+pub trait PllLogger {
+    /// Feedback divider, integer and 32-bit fraction
+    fn log_feedback_divider(&self, _integer: u32, _frac: u32) {}
+
+    /// Output divider, integer and 32-bit fraction
+    fn log_output_divider(&self, _output: OutputChannel, _integer: u32, _frac: u32) {}
+
+    /// VCO band index found by the calibration
+    fn log_vco_index(&self, _index: u8) {}
+
+    /// Configuration with 1.8 V outputs is written
+    fn log_output_voltage_v18(&self) {}
+
+    /// `switch_frequency_plan` finished, duration in microseconds
+    fn log_plan_switch(&self, _elapsed_us: u64) {}
+
+    /// The chip no longer holds the configuration and is reprogrammed, see
+    /// `pll_monitor_task`
+    fn log_configuration_lost(&self) {}
+}
+
+/// Logger discarding all output
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoopLogger;
+
+impl PllLogger for NoopLogger {}
+
+/// Placeholder for a driver without an OE pin, see `Pll5p49v::with_oe_pin`
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct NoOePin;
+
+impl embedded_hal::digital::ErrorType for NoOePin {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for NoOePin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// Logger printing with `defmt::debug!`, 1.8 V outputs and a lost
+/// configuration with `defmt::warn!`
+#[cfg(feature = "defmt")]
+#[derive(defmt::Format)]
+pub struct DefmtLogger;
+
+#[cfg(feature = "defmt")]
+impl PllLogger for DefmtLogger {
+    fn log_feedback_divider(&self, integer: u32, frac: u32) {
+        defmt::debug!(
+            "feedback_divider, int: {}, frac: {} ({})",
+            integer,
+            frac,
+            (frac as f64) / ((1u64 << 32) as f64)
+        );
+    }
+
+    fn log_output_divider(&self, output: OutputChannel, integer: u32, frac: u32) {
+        defmt::debug!(
+            "od{}, int: {}, frac: {} ({})",
+            output.index() + 1,
+            integer,
+            frac,
+            (frac as f64) / ((1u64 << 32) as f64)
+        );
+    }
+
+    fn log_vco_index(&self, index: u8) {
+        defmt::debug!("VCO index: {}", index);
+    }
+
+    fn log_output_voltage_v18(&self) {
+        defmt::warn!("Outputs set up for 1.8 V, VDDO supplies must match");
+    }
+
+    fn log_plan_switch(&self, elapsed_us: u64) {
+        defmt::info!("Frequency plan switched in {} us", elapsed_us);
+    }
+
+    fn log_configuration_lost(&self) {
+        defmt::warn!("PLL configuration lost, reprogramming");
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Chip generation, selects the generation specific behavior of `Pll5p49v`
+pub trait ChipGeneration: sealed::Sealed {
+    /// Generation as a value, e.g. for the configuration builder
+    const GENERATION: Generation;
+    /// Known-good configuration of the reference chip of the generation
+    const DEFAULT_CONFIG: Pll5p49vConfig;
+}
+
+/// 5th generation (5P49V59xx): VCO calibration is automatic, no lock-detect bit
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Gen5;
+
+/// 6th generation (5P49V69xx): VCO calibration must be triggered after programming
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Gen6;
+
+impl sealed::Sealed for Gen5 {}
+impl sealed::Sealed for Gen6 {}
+
+impl ChipGeneration for Gen5 {
+    const GENERATION: Generation = Generation::Fifth;
+    const DEFAULT_CONFIG: Pll5p49vConfig = DEFAULT_CONFIG_5923;
+}
+
+impl ChipGeneration for Gen6 {
+    const GENERATION: Generation = Generation::Sixth;
+    const DEFAULT_CONFIG: Pll5p49vConfig = DEFAULT_CONFIG_6965;
+}
+
+/// Driver state, tracked in the type so that the call order is checked at compile time
+///
+/// `Uninitialized` --`write_config`--> `Configured` --`calibrate_vco`--> `Calibrated`.
+/// `write_config` is accepted in any state and `soft_reset` goes back to `Uninitialized`.
+pub trait PllState: sealed::Sealed {}
+
+/// States with the chip programmed by `write_config`, output control is available
+pub trait Initialized: PllState {}
+
+/// Chip registers hold the power-up defaults, not a configuration of the driver
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Uninitialized;
+
+/// Configuration written, VCO not calibrated yet
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Configured;
+
+/// Configuration written and VCO calibrated
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Calibrated;
+
+impl sealed::Sealed for Uninitialized {}
+impl sealed::Sealed for Configured {}
+impl sealed::Sealed for Calibrated {}
+
+impl PllState for Uninitialized {}
+impl PllState for Configured {}
+impl PllState for Calibrated {}
+
+impl Initialized for Configured {}
+impl Initialized for Calibrated {}
+
+/// 5P49V clock generator driver
+///
+/// The driver takes the I2C bus by value. Any `embedded_hal_async::i2c::I2c`
+/// implementation can be used, so the caller chooses between:
+/// - moving the bus in (e.g. `I2c<'static, I2C1, Async>`), when the chip is the
+///   only device on the bus; `release` hands the bus back,
+/// - passing `&mut I2c<..>`, which borrows the bus for the lifetime of the driver,
+/// - passing a shared bus device (e.g. `embassy_embedded_hal` `I2cDevice`).
+///
+/// Ownership fits the Embassy model of peripherals moved into tasks, so no
+/// lifetime parameter is needed on the driver itself.
+///
+/// Nothing in the driver is specific to the RP2040 HAL, it runs on any MCU
+/// with an `embedded-hal-async` I2C implementation. The waits of `soft_reset`
+/// and `write_config` use `embassy_time`, `calibrate_vco_with_delay` takes
+/// any `DelayNs`.
+///
+/// The state `S` (see `PllState`) changes on `write_config`, `calibrate_vco`
+/// and `soft_reset`, which consume the driver and return it in the new state.
+/// On failure the driver is handed back unchanged together with the error.
+///
+/// An optional GPIO on the OE pin (`O`, see `with_oe_pin`) gates the outputs
+/// during `write_config`.
+pub struct Pll5p49v<
+    I2C,
+    G: ChipGeneration,
+    S: PllState = Uninitialized,
+    L: PllLogger = NoopLogger,
+    O: OutputPin = NoOePin,
+> {
+    i2c: I2C,
+    address: u8,
+    logger: L,
+    oe_pin: Option<O>,
+    cache: RegisterCache,
+    _generation: PhantomData<G>,
+    _state: PhantomData<S>,
+}
+
+/// Number of configuration registers covered by `write_config` (0x00 - 0x69)
+const CONFIG_REGISTER_COUNT: usize = PROG_ARRAY_SIZE - 1;
+
+/// Last value written to each configuration register, `None` if unknown
+#[derive(Clone)]
+struct RegisterCache([Option<u8>; CONFIG_REGISTER_COUNT]);
+
+impl RegisterCache {
+    /// Nothing known, the next `write_config` writes every register
+    const EMPTY: Self = RegisterCache([None; CONFIG_REGISTER_COUNT]);
+
+    /// Record a register write, registers above 0x69 are not cached
+    fn update(&mut self, reg: u8, value: Option<u8>) {
+        if let Some(entry) = self.0.get_mut(reg as usize) {
+            *entry = value;
+        }
+    }
+
+    /// First run of registers from `from` on whose cached value differs from
+    /// `values` (indexed by register), as a half-open range; registers marked
+    /// in `skip` never count as changed
+    fn next_changed_run(
+        &self,
+        values: &[u8],
+        skip: &[bool; CONFIG_REGISTER_COUNT],
+        from: usize,
+    ) -> Option<(usize, usize)> {
+        let changed = |reg: usize| !skip[reg] && self.0[reg] != Some(values[reg]);
+
+        let start = (from..CONFIG_REGISTER_COUNT).find(|&reg| changed(reg))?;
+        let end = (start..CONFIG_REGISTER_COUNT)
+            .find(|&reg| !changed(reg))
+            .unwrap_or(CONFIG_REGISTER_COUNT);
+
+        Some((start, end))
+    }
+}
+
+/// Registers `write_config` leaves untouched: the dividers (0xX2 - 0xXE) of
+/// disabled outputs
+fn unwritten_registers(config: &Pll5p49vConfig) -> [bool; CONFIG_REGISTER_COUNT] {
+    let mut skip = [false; CONFIG_REGISTER_COUNT];
+
+    for (output, output_fq_hz) in OutputChannel::ALL.iter().zip(config.outputs) {
+        if output_fq_hz.is_none() {
+            let first = (output.divider_control_reg() + 1) as usize;
+
+            skip[first..first + 13].fill(true);
+        }
+    }
+
+    skip
+}
+
+/// Register writes turning one configuration into another, see `Pll5p49vConfig::diff`
+#[derive(Clone)]
+pub struct RegisterDiff {
+    writes: [(u8, u8); CONFIG_REGISTER_COUNT],
+    len: usize,
+}
+
+impl RegisterDiff {
+    /// Changed registers as `(register, new value)`, in register order
+    pub fn writes(&self) -> &[(u8, u8)] {
+        &self.writes[..self.len]
+    }
+
+    /// Number of changed registers
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// No register differs
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl core::fmt::Debug for RegisterDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.writes()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RegisterDiff {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[?]}", self.writes());
+    }
+}
+
+impl Pll5p49vConfig {
+    /// Registers to write to change the chip from configuration `a` to `b`
+    ///
+    /// Compares the register sets of both configurations, the dividers of
+    /// outputs disabled in `b` are left out as in `write_config`. The
+    /// divider registers (0xX1 - 0xXF) of an output disabled in `a` hold
+    /// whatever was there before, they are always written.
+    pub fn diff(a: &Pll5p49vConfig, b: &Pll5p49vConfig) -> Result<RegisterDiff, ConfigError> {
+        let from = build_prog_array(a)?;
+        let to = build_prog_array(b)?;
+        let skip = unwritten_registers(b);
+
+        let mut unknown = [false; CONFIG_REGISTER_COUNT];
+
+        for (output, output_fq_hz) in OutputChannel::ALL.iter().zip(a.outputs) {
+            if output_fq_hz.is_none() {
+                let first = output.divider_control_reg() as usize;
+
+                unknown[first..first + 15].fill(true);
+            }
+        }
+
+        let mut diff = RegisterDiff {
+            writes: [(0, 0); CONFIG_REGISTER_COUNT],
+            len: 0,
+        };
+
+        // The register arrays start with the register address
+        for reg in 0..CONFIG_REGISTER_COUNT {
+            if !skip[reg] && (unknown[reg] || from[reg + 1] != to[reg + 1]) {
+                diff.writes[diff.len] = (reg as u8, to[reg + 1]);
+                diff.len += 1;
+            }
+        }
+
+        Ok(diff)
+    }
+}
+
+/// Writes to consecutive registers collected into one I2C transaction
+struct BurstWriter {
+    start_reg: u8,
+    /// Start address followed by up to 31 register values
+    buffer: [u8; 32],
+    len: usize,
+}
+
+impl BurstWriter {
+    const EMPTY: Self = BurstWriter {
+        start_reg: 0,
+        buffer: [0u8; 32],
+        len: 0,
+    };
+
+    /// Append a register, fails if it does not follow the last one or the burst is full
+    fn add(&mut self, reg: u8, value: u8) -> Result<(), ()> {
+        if self.len == 0 {
+            self.start_reg = reg;
+            self.buffer[0] = reg;
+        } else if reg as usize != self.start_reg as usize + self.len
+            || self.len + 1 == self.buffer.len()
+        {
+            return Err(());
+        }
+
+        self.len += 1;
+        self.buffer[self.len] = value;
+
+        Ok(())
+    }
+
+    /// Values of the collected registers, starting at `start_reg`
+    fn values(&self) -> &[u8] {
+        &self.buffer[1..=self.len]
+    }
+
+    /// Write the collected registers with the start address prepended
+    async fn flush<I2C, E>(&self, i2c: &mut I2C, address: u8) -> Result<(), Pll5p49vError<E>>
+    where
+        I2C: embedded_hal_async::i2c::I2c<Error = E>,
+    {
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        i2c.write(address, &self.buffer[..=self.len])
+            .await
+            .map_err(Pll5p49vError::I2c)
+    }
+}
+
+/// Chip generation and I2C address, the bus and logger are not printed
+impl<I2C, G: ChipGeneration, S: PllState, L: PllLogger, O: OutputPin> core::fmt::Debug
+    for Pll5p49v<I2C, G, S, L, O>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pll5p49v")
+            .field("generation", &G::GENERATION)
+            .field("address", &format_args!("{:#04x}", self.address))
+            .finish()
+    }
+}
+
+/// Chip generation and I2C address, the bus and logger are not printed
+#[cfg(feature = "defmt")]
+impl<I2C, G: ChipGeneration, S: PllState, L: PllLogger, O: OutputPin> defmt::Format
+    for Pll5p49v<I2C, G, S, L, O>
+{
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Pll5p49v({}, address: {=u8:#04x})",
+            G::GENERATION,
+            self.address
+        );
+    }
+}
+
+/// Driver for the 5P49V6965, e.g. with `DEFAULT_CONFIG_6965`
+pub type Pll5p49v6965<I2C> = Pll5p49v<I2C, Gen6>;
+
+/// Driver for the 5P49V5923, e.g. with `DEFAULT_CONFIG_5923`
+pub type Pll5p49v5923<I2C> = Pll5p49v<I2C, Gen5>;
+
+impl<I2C, G: ChipGeneration> Pll5p49v<I2C, G> {
+    /// Create a new driver instance for the default I2C address 0x6A
+    ///
+    /// The bus is not accessed, a missing chip shows up at the first
+    /// transfer. `new_probed` checks the address first.
+    pub fn new_unchecked(i2c: I2C) -> Self {
+        Self::new_with_address(i2c, Address::Default)
+    }
+
+    /// Create a new driver instance for the given I2C address
+    pub fn new_with_address(i2c: I2C, address: Address) -> Self {
+        Self {
+            i2c,
+            address: address.addr(),
+            logger: NoopLogger,
+            oe_pin: None,
+            cache: RegisterCache::EMPTY,
+            _generation: PhantomData,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<I2C, E, G: ChipGeneration> Pll5p49v<I2C, G>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Create a new driver instance for the default I2C address 0x6A if the
+    /// chip acknowledges its address
+    ///
+    /// Writes the register pointer (0x00), zero-length writes are not
+    /// supported by every I2C controller, e.g. the RP2040 / RP2350. A missing
+    /// ACK fails with `DeviceNotFound`, other bus errors with `I2c`. The I2C
+    /// bus is handed back on failure.
+    pub async fn new_probed(i2c: I2C) -> Result<Self, (I2C, Pll5p49vError<E>)>
+    where
+        E: embedded_hal::i2c::Error,
+    {
+        let mut pll = Self::new_unchecked(i2c);
+
+        match pll.i2c.write(pll.address, &[regs::START]).await {
+            Ok(()) => Ok(pll),
+            Err(e) => {
+                let e = match e.kind() {
+                    embedded_hal::i2c::ErrorKind::NoAcknowledge(_) => Pll5p49vError::DeviceNotFound,
+                    _ => Pll5p49vError::I2c(e),
+                };
+
+                Err((pll.release(), e))
+            }
+        }
+    }
+
+    /// Create a driver instance after checking the chip with `read_device_id`
+    ///
+    /// The I2C bus is handed back on failure.
+    pub async fn probe(i2c: I2C, address: Address) -> Result<Self, (I2C, Pll5p49vError<E>)> {
+        let mut pll = Self::new_with_address(i2c, address);
+
+        match pll.read_device_id().await {
+            Ok(_) => Ok(pll),
+            Err(e) => Err((pll.release(), e)),
+        }
+    }
+}
+
+impl<I2C, G: ChipGeneration, S: PllState, L: PllLogger, O: OutputPin> Pll5p49v<I2C, G, S, L, O> {
+    /// Replace the debug output logger
+    pub fn with_logger<L2: PllLogger>(self, logger: L2) -> Pll5p49v<I2C, G, S, L2, O> {
+        Pll5p49v {
+            i2c: self.i2c,
+            address: self.address,
+            logger,
+            oe_pin: self.oe_pin,
+            cache: self.cache,
+            _generation: PhantomData,
+            _state: PhantomData,
+        }
+    }
+
+    /// Gate the outputs with a GPIO wired to the OE pin, see `assert_oe`
+    pub fn with_oe_pin<O2: OutputPin>(self, oe_pin: O2) -> Pll5p49v<I2C, G, S, L, O2> {
+        Pll5p49v {
+            i2c: self.i2c,
+            address: self.address,
+            logger: self.logger,
+            oe_pin: Some(oe_pin),
+            cache: self.cache,
+            _generation: PhantomData,
+            _state: PhantomData,
+        }
+    }
+
+    /// Disable all outputs with the OE pin, no-op without an OE pin
+    ///
+    /// The level follows `Pll5p49vConfig::oe_polarity` as last written by
+    /// `write_config`, active high before that or after `invalidate_cache`.
+    pub fn assert_oe<E>(&mut self) -> Result<(), Pll5p49vError<E>> {
+        let polarity = self.oe_polarity();
+
+        self.set_oe_pin(polarity == OePolarity::ActiveLow)
+    }
+
+    /// Enable the outputs again with the OE pin, no-op without an OE pin
+    pub fn deassert_oe<E>(&mut self) -> Result<(), Pll5p49vError<E>> {
+        let polarity = self.oe_polarity();
+
+        self.set_oe_pin(polarity == OePolarity::ActiveHigh)
+    }
+
+    /// SD/OE polarity of the chip as far as the register cache knows it
+    fn oe_polarity(&self) -> OePolarity {
+        match self.cache.0[regs::PRIMARY_SRC_SHDN as usize] {
+            Some(value) if value & regs::PRIMARY_SRC_SHDN_SP != 0 => OePolarity::ActiveLow,
+            _ => OePolarity::ActiveHigh,
+        }
+    }
+
+    /// Drive the OE pin high or low
+    fn set_oe_pin<E>(&mut self, high: bool) -> Result<(), Pll5p49vError<E>> {
+        let result = match &mut self.oe_pin {
+            Some(pin) if high => pin.set_high(),
+            Some(pin) => pin.set_low(),
+            None => return Ok(()),
+        };
+
+        result.map_err(|_| Pll5p49vError::OePin)
+    }
+
+    /// Release the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    fn into_state<S2: PllState>(self) -> Pll5p49v<I2C, G, S2, L, O> {
+        Pll5p49v {
+            i2c: self.i2c,
+            address: self.address,
+            logger: self.logger,
+            oe_pin: self.oe_pin,
+            cache: self.cache,
+            _generation: PhantomData,
+            _state: PhantomData,
+        }
+    }
+}
+
+impl<I2C, E, G: ChipGeneration, S: PllState, L: PllLogger, O: OutputPin> Pll5p49v<I2C, G, S, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Read a single register
+    ///
+    /// Low-level access for bring-up and debugging, prefer the typed methods.
+    pub async fn read_register(&mut self, reg: u8) -> Result<u8, Pll5p49vError<E>> {
+        let mut resp_buff = [0u8; 1];
+        self.i2c
+            .write_read(self.address, &[reg], &mut resp_buff)
+            .await
+            .map_err(Pll5p49vError::I2c)?;
+
+        Ok(resp_buff[0])
+    }
+
+    /// Read consecutive registers starting at `reg` in a single I2C burst
+    pub async fn read_registers(
+        &mut self,
+        reg: u8,
+        buf: &mut [u8],
+    ) -> Result<(), Pll5p49vError<E>> {
+        self.i2c
+            .write_read(self.address, &[reg], buf)
+            .await
+            .map_err(Pll5p49vError::I2c)
+    }
+
+    /// Write a single register
+    ///
+    /// Warning: bypasses the typed API, writing an arbitrary value can leave
+    /// the chip in an inconsistent state (e.g. wrong dividers, disabled outputs).
+    pub async fn write_register(&mut self, reg: u8, value: u8) -> Result<(), Pll5p49vError<E>> {
+        let result = self
+            .i2c
+            .write(self.address, &[reg, value])
+            .await
+            .map_err(Pll5p49vError::I2c);
+
+        self.cache.update(reg, result.is_ok().then_some(value));
+
+        result
+    }
+
+    /// Write consecutive registers starting at `reg` in a single I2C burst
+    async fn write_registers(&mut self, reg: u8, values: &[u8]) -> Result<(), Pll5p49vError<E>> {
+        let mut burst = [0u8; PROG_ARRAY_SIZE];
+        burst[0] = reg;
+        burst[1..=values.len()].copy_from_slice(values);
+
+        let result = self
+            .i2c
+            .write(self.address, &burst[..=values.len()])
+            .await
+            .map_err(Pll5p49vError::I2c);
+
+        for (reg, &value) in (reg..).zip(values) {
+            self.cache.update(reg, result.is_ok().then_some(value));
+        }
+
+        result
+    }
+
+    /// Forget the register values remembered by `write_config`
+    ///
+    /// Call this after the chip was changed behind the driver's back (power
+    /// cycle, another bus master), the next `write_config` then writes every
+    /// register.
+    pub fn invalidate_cache(&mut self) {
+        self.cache = RegisterCache::EMPTY;
+    }
+
+    /// Update the bits selected by `mask` in a register, other bits are preserved
+    pub async fn read_modify_write(
+        &mut self,
+        reg: u8,
+        mask: u8,
+        value: u8,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let current = self.read_register(reg).await?;
+
+        self.write_register(reg, (current & !mask) | (value & mask))
+            .await
+    }
+
+    /// Write configuration registers
+    ///
+    /// Accepted in any state, a calibrated driver goes back to `Configured`
+    /// since a new configuration needs a new VCO calibration.
+    ///
+    /// With an OE pin (see `with_oe_pin`) the outputs are disabled while the
+    /// registers are written.
+    ///
+    /// Only registers that differ from the last written values are sent, each
+    /// run of consecutive changed registers as one burst. Registers with an
+    /// unknown value always count as changed, so the first call after
+    /// `new_unchecked` or `soft_reset` writes the full register set. The
+    /// dividers of disabled outputs (see `Pll5p49vConfig::outputs`) are not
+    /// written at all.
+    ///
+    /// Returns after `Pll5p49vConfig::stabilization_ms`, the time the PLL
+    /// needs to lock.
+    pub async fn write_config(
+        mut self,
+        config: &Pll5p49vConfig,
+    ) -> Result<Pll5p49v<I2C, G, Configured, L, O>, (Self, Pll5p49vError<E>)> {
+        if let Err(e) = self.write_prog_array(config).await {
+            return Err((self, e));
+        }
+
+        if config.stabilization_ms > 0 {
+            Timer::after_millis(config.stabilization_ms as u64).await;
+        }
+
+        Ok(self.into_state())
+    }
+
+    async fn write_prog_array(&mut self, config: &Pll5p49vConfig) -> Result<(), Pll5p49vError<E>> {
+        let prog_array = build_prog_array(config)?;
+
+        let feedback_divider = compute_feedback_divider(config.vco_fq_hz, config.clock_fq_hz);
+
+        self.logger
+            .log_feedback_divider((feedback_divider >> 32) as u32, feedback_divider as u32);
+
+        for (output, output_fq_hz) in OutputChannel::ALL.iter().zip(config.outputs) {
+            let Some(output_fq_hz) = output_fq_hz else {
+                continue;
+            };
+
+            let od = compute_output_divider(config.vco_fq_hz, output_fq_hz);
+
+            self.logger
+                .log_output_divider(*output, (od >> 32) as u32, od as u32);
+        }
+
+        if config.global_output.voltage == OutputVoltage::V18 {
+            self.logger.log_output_voltage_v18();
+        }
+
+        // prog_array starts with the register address
+        let values = &prog_array[1..];
+        let skip = unwritten_registers(config);
+
+        // Outputs stay disabled if the write fails
+        self.assert_oe()?;
+
+        let mut from = 0;
+
+        while let Some((start, end)) = self.cache.next_changed_run(values, &skip, from) {
+            let mut burst = [0u8; PROG_ARRAY_SIZE];
+            burst[0] = start as u8;
+            burst[1..=end - start].copy_from_slice(&values[start..end]);
+
+            if let Err(e) = self.i2c.write(self.address, &burst[..=end - start]).await {
+                // Part of the burst may have been taken
+                self.cache = RegisterCache::EMPTY;
+                return Err(Pll5p49vError::I2c(e));
+            }
+
+            for (entry, &value) in self.cache.0[start..end].iter_mut().zip(&values[start..end]) {
+                *entry = Some(value);
+            }
+
+            from = end;
+        }
+
+        self.deassert_oe()
+    }
+
+    /// Write the registers of a `RegisterDiff`, consecutive registers as one burst
+    ///
+    /// Meant for a chip programmed with the first configuration of the diff.
+    /// Stops at the first failure, earlier bursts are written.
+    pub async fn apply_diff(&mut self, diff: &RegisterDiff) -> Result<(), Pll5p49vError<E>> {
+        let mut burst = BurstWriter::EMPTY;
+
+        for &(reg, value) in diff.writes() {
+            if burst.add(reg, value).is_err() {
+                self.write_burst(&burst).await?;
+
+                burst = BurstWriter::EMPTY;
+                // An empty burst takes any register
+                let _ = burst.add(reg, value);
+            }
+        }
+
+        self.write_burst(&burst).await
+    }
+
+    async fn write_burst(&mut self, burst: &BurstWriter) -> Result<(), Pll5p49vError<E>> {
+        let result = burst.flush(&mut self.i2c, self.address).await;
+
+        for (reg, &value) in (burst.start_reg..).zip(burst.values()) {
+            self.cache.update(reg, result.is_ok().then_some(value));
+        }
+
+        result
+    }
+
+    /// Read the enable bit, output configuration and divider of an output
+    ///
+    /// Fails with `HardwareNotResponding` if the format bits hold a reserved value.
+    pub async fn read_output_status(
+        &mut self,
+        output: OutputChannel,
+    ) -> Result<OutputStatus, Pll5p49vError<E>> {
+        let output_en = self.read_register(regs::OUTPUT_EN).await?;
+
+        let output_cfg = self.read_register(output.config_reg()).await?;
+
+        let od = self.read_output_dividers(output).await?;
+
+        let format =
+            OutputFormat::from_reg(output_cfg).ok_or(Pll5p49vError::HardwareNotResponding)?;
+
+        Ok(OutputStatus {
+            enabled: output_en & output.enable_mask() != 0,
+            format,
+            drive_strength: DriveStrength::from_reg(output_cfg),
+            divider_integer: od.integer,
+            divider_fractional: od.fractional,
+        })
+    }
+
+    /// Read back the divider registers and compare them with the values for `config`
+    ///
+    /// Checks the feedback divider (0x17 - 0x1B) and the output dividers
+    /// (0x22 - 0x2E, 0x32 - 0x3E, 0x42 - 0x4E, 0x52 - 0x5E) of enabled outputs.
+    pub async fn verify_config(&mut self, config: &Pll5p49vConfig) -> Result<(), Pll5p49vError<E>> {
+        const RANGES: [(u8, u8); 5] = [
+            (regs::FEEDBACK_DIV_INT_HI, regs::FEEDBACK_DIV_FRAC_2),
+            (regs::OD1_FRAC_0, regs::OD1_INT_LO),
+            (
+                regs::OD1_FRAC_0 + regs::OD_STRIDE,
+                regs::OD1_INT_LO + regs::OD_STRIDE,
+            ),
+            (
+                regs::OD1_FRAC_0 + 2 * regs::OD_STRIDE,
+                regs::OD1_INT_LO + 2 * regs::OD_STRIDE,
+            ),
+            (
+                regs::OD1_FRAC_0 + 3 * regs::OD_STRIDE,
+                regs::OD1_INT_LO + 3 * regs::OD_STRIDE,
+            ),
+        ];
+
+        let prog_array = build_prog_array(config)?;
+        let skip = unwritten_registers(config);
+
+        for (first, last) in RANGES {
+            // Dividers of disabled outputs are not written
+            if skip[first as usize] {
+                continue;
+            }
+
+            let mut resp_buff = [0u8; 13];
+            let resp_buff = &mut resp_buff[..=(last - first) as usize];
+
+            self.read_registers(first, resp_buff).await?;
+
+            for (reg, &actual) in (first..=last).zip(resp_buff.iter()) {
+                // prog_array starts with the register address
+                let expected = prog_array[reg as usize + 1];
+
+                if actual != expected {
+                    return Err(Pll5p49vError::Mismatch {
+                        reg,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read the device configuration register (0x00)
+    ///
+    /// The chip has no fixed ID register, register 0x00 holds OTP configuration
+    /// bits that differ between parts. Only 0x00 and 0xFF, the values of an
+    /// absent chip or a floating bus, are refused with `UnexpectedDeviceId`.
+    pub async fn read_device_id(&mut self) -> Result<u8, Pll5p49vError<E>> {
+        let id = self.read_register(regs::DEVICE_ID).await?;
+
+        if id == 0x00 || id == 0xFF {
+            return Err(Pll5p49vError::UnexpectedDeviceId { got: id });
+        }
+
+        Ok(id)
+    }
+
+    /// Read the VCO band index found by the last calibration (register 0x99, bits 7:3)
+    ///
+    /// Only reads the status, the calibration is not triggered.
+    pub async fn read_vco_index(&mut self) -> Result<u8, Pll5p49vError<E>> {
+        let vco_status = self.read_register(regs::VCO_STATUS).await?;
+
+        Ok(vco_status >> 3)
+    }
+
+    /// Read the whole register map in a single I2C burst
+    pub async fn dump_registers(&mut self) -> Result<[u8; REGISTER_MAP_SIZE], Pll5p49vError<E>> {
+        let mut dump = [0u8; REGISTER_MAP_SIZE];
+        self.read_registers(regs::START, &mut dump).await?;
+
+        Ok(dump)
+    }
+
+    /// Read the configuration registers and the VCO status for a bug report
+    ///
+    /// Two transfers: a burst of 0x00 - 0x69 and the status register 0x99.
+    /// The chip has no temperature sensor, only the register state is captured.
+    pub async fn read_diagnostics(&mut self) -> Result<PllDiagnostics, Pll5p49vError<E>> {
+        let mut register_snapshot = [0u8; CONFIG_REGISTER_COUNT];
+        self.read_registers(regs::START, &mut register_snapshot)
+            .await?;
+
+        let vco_status = self.read_register(regs::VCO_STATUS).await?;
+
+        Ok(PllDiagnostics {
+            register_snapshot,
+            vco_index: vco_status >> 3,
+        })
+    }
+
+    /// Read the divider of an output
+    ///
+    /// The fraction (0xX2 - 0xX5) and integer (0xXD - 0xXE) registers are read
+    /// in one burst together with the spread and skew registers between them:
+    /// a single I2C transaction instead of one per register.
+    pub async fn read_output_dividers(
+        &mut self,
+        output: OutputChannel,
+    ) -> Result<OutputDividerRegs, Pll5p49vError<E>> {
+        let mut od_regs = [0u8; 13];
+        self.read_registers(output.divider_control_reg() + 1, &mut od_regs)
+            .await?;
+
+        let od = unpack_output_divider(&od_regs);
+
+        Ok(OutputDividerRegs {
+            integer: (od >> 32) as u32,
+            fractional: od as u32,
+        })
+    }
+
+    /// Read back the programmed frequency of an output from the divider registers
+    pub async fn read_output_frequency(
+        &mut self,
+        output: OutputChannel,
+        reference_fq_hz: u32,
+    ) -> Result<u32, Pll5p49vError<E>> {
+        let mut feedback_regs = [0u8; 5];
+        self.read_registers(regs::FEEDBACK_DIV_INT_HI, &mut feedback_regs)
+            .await?;
+
+        let od = self.read_output_dividers(output).await?;
+
+        let vco_fq_hz = vco_frequency(unpack_feedback_divider(&feedback_regs), reference_fq_hz);
+
+        Ok(compute_actual_frequency(vco_fq_hz, od.divider()))
+    }
+
+    /// Software reset: set the reset bit (register 0x01, bit 7) and wait up to
+    /// 10 ms for it to self-clear, then check register 0x00 with `read_device_id`
+    ///
+    /// The chip returns to its power-up defaults, so does the driver state.
+    pub async fn soft_reset(
+        mut self,
+    ) -> Result<Pll5p49v<I2C, G, Uninitialized, L, O>, (Self, Pll5p49vError<E>)> {
+        match self.reset().await {
+            Ok(()) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    async fn reset(&mut self) -> Result<(), Pll5p49vError<E>> {
+        // The registers go back to their power-up values
+        self.cache = RegisterCache::EMPTY;
+
+        self.read_modify_write(
+            regs::RESET_CTRL,
+            regs::RESET_CTRL_SOFT_RESET,
+            regs::RESET_CTRL_SOFT_RESET,
+        )
+        .await?;
+
+        let mut cleared = false;
+
+        for _ in 0..10 {
+            Timer::after_millis(1).await;
+
+            // The chip may not acknowledge while in reset
+            if let Ok(value) = self.read_register(regs::RESET_CTRL).await {
+                if value & regs::RESET_CTRL_SOFT_RESET == 0 {
+                    cleared = true;
+                    break;
+                }
+            }
+        }
+
+        if !cleared {
+            return Err(Pll5p49vError::HardwareNotResponding);
+        }
+
+        self.read_device_id().await?;
+
+        Ok(())
+    }
+
+    /// First half of `switch_frequency_plan`: disable OUT1..OUT4 and write
+    /// `new_config` with them held off, returns the register 0x68 value of
+    /// `new_config`
+    async fn write_with_outputs_held(
+        &mut self,
+        new_config: &Pll5p49vConfig,
+    ) -> Result<u8, Pll5p49vError<E>> {
+        let prog_array = build_prog_array(new_config)?;
+
+        let mut quiet_config = new_config.clone();
+
+        for clock_output in quiet_config.clock_outputs.iter_mut() {
+            clock_output.enabled = false;
+        }
+
+        self.write_register(regs::OUTPUT_EN, quiet_config.output_enable_reg())
+            .await?;
+
+        self.write_prog_array(&quiet_config).await?;
+
+        // prog_array starts with the register address
+        Ok(prog_array[regs::OUTPUT_EN as usize + 1])
+    }
+
+    /// Second half of `switch_frequency_plan`: wait
+    /// `Pll5p49vConfig::stabilization_ms` and enable the outputs
+    async fn release_outputs(
+        &mut self,
+        new_config: &Pll5p49vConfig,
+        output_en: u8,
+    ) -> Result<(), Pll5p49vError<E>> {
+        if new_config.stabilization_ms > 0 {
+            Timer::after_millis(new_config.stabilization_ms as u64).await;
+        }
+
+        self.write_register(regs::OUTPUT_EN, output_en).await
+    }
+
+    /// Make the SD/OE pin a shutdown input by setting SH (register 0x10, bit 0)
+    ///
+    /// The bit alone does not power anything down: the chip shuts down while
+    /// the pin is asserted at `Pll5p49vConfig::oe_polarity` and runs while it
+    /// is not. With the bit cleared the pin only gates the outputs. Drive the
+    /// pin (e.g. `assert_oe` with an OE pin given) to enter the shutdown.
+    pub async fn power_down(&mut self) -> Result<(), Pll5p49vError<E>> {
+        self.read_modify_write(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN,
+            regs::PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN,
+        )
+        .await
+    }
+
+    /// Make the SD/OE pin an output enable again by clearing SH (register
+    /// 0x10, bit 0), which ends a shutdown held by the pin
+    pub async fn power_up(&mut self) -> Result<(), Pll5p49vError<E>> {
+        self.read_modify_write(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN,
+            0,
+        )
+        .await
+    }
+
+    /// Read SH (register 0x10, bit 0), the shutdown function of the SD/OE pin
+    ///
+    /// A set bit does not mean the chip is shut down, see `power_down`.
+    pub async fn is_powered_down(&mut self) -> Result<bool, Pll5p49vError<E>> {
+        let value = self.read_register(regs::PRIMARY_SRC_SHDN).await?;
+
+        Ok(value & regs::PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN != 0)
+    }
+}
+
+impl<I2C, E, G: ChipGeneration, S: Initialized, L: PllLogger, O: OutputPin>
+    Pll5p49v<I2C, G, S, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Enable or disable a single clock output, other outputs are not affected
+    pub async fn enable_output(
+        &mut self,
+        output: OutputChannel,
+        enabled: bool,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let mask = output.enable_mask();
+
+        self.read_modify_write(regs::OUTPUT_EN, mask, if enabled { mask } else { 0 })
+            .await
+    }
+
+    /// Read the enable state of a clock output
+    pub async fn output_enabled(
+        &mut self,
+        output: OutputChannel,
+    ) -> Result<bool, Pll5p49vError<E>> {
+        let value = self.read_register(regs::OUTPUT_EN).await?;
+
+        Ok(value & output.enable_mask() != 0)
+    }
+
+    /// Set the signal format of a clock output, other output settings are preserved
+    ///
+    /// LVDS is refused with `NotApplicableForFormat` when the outputs are set to 1.8 V.
+    pub async fn set_output_format(
+        &mut self,
+        output: OutputChannel,
+        format: OutputFormat,
+    ) -> Result<(), Pll5p49vError<E>> {
+        if format == OutputFormat::Lvds {
+            let output_ctrl = self.read_register(regs::OUTPUT_CTRL).await?;
+
+            if output_ctrl & regs::OUTPUT_CTRL_V18 != 0 {
+                return Err(Pll5p49vError::NotApplicableForFormat);
+            }
+        }
+
+        self.read_modify_write(output.config_reg(), OutputFormat::MASK, format.bits())
+            .await
+    }
+
+    /// Set the drive strength of a clock output, the output must be in LVCMOS mode
+    pub async fn set_output_drive_strength(
+        &mut self,
+        output: OutputChannel,
+        strength: DriveStrength,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let reg = output.config_reg();
+
+        let value = self.read_register(reg).await?;
+
+        if value & OutputFormat::MASK != OutputFormat::Lvcmos.bits() {
+            return Err(Pll5p49vError::InvalidModeForOperation);
+        }
+
+        self.write_register(reg, (value & !DriveStrength::MASK) | strength.bits())
+            .await
+    }
+
+    /// Set the slew rate of a clock output, the field width depends on the output format
+    pub async fn set_output_slew_rate(
+        &mut self,
+        output: OutputChannel,
+        rate: SlewRate,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let reg = output.config_reg();
+
+        let value = self.read_register(reg).await?;
+
+        let (mask, bits) = OutputFormat::from_reg(value)
+            .and_then(|format| rate.field(format))
+            .ok_or(Pll5p49vError::NotApplicableForFormat)?;
+
+        self.write_register(reg, (value & !mask) | bits).await
+    }
+
+    /// Set the polarity of a clock output, other output settings are preserved
+    ///
+    /// Bit 0 of the second output configuration register (0x61, 0x63, 0x65, 0x67)
+    /// is set for the normal and cleared for the inverted output.
+    pub async fn set_output_polarity(
+        &mut self,
+        output: OutputChannel,
+        inverted: bool,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let value = if inverted {
+            0
+        } else {
+            regs::CLK_CFG_1_NORMAL_POLARITY
+        };
+
+        self.read_modify_write(
+            output.config_reg() + 1,
+            regs::CLK_CFG_1_NORMAL_POLARITY,
+            value,
+        )
+        .await
+    }
+
+    /// Select the divider driving an output pin
+    ///
+    /// The 5P49V has no free routing between dividers and pins: OUTn is always
+    /// driven by ODn (SEL_EXT of OD2..OD4 cascades the previous divider into the
+    /// next one, it does not reroute it). Other combinations, and bypass on
+    /// anything but OUT1, return `InvalidModeForOperation`.
+    pub async fn configure_output_mux(
+        &mut self,
+        output: OutputChannel,
+        source: DividerSource,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let mode = source
+            .mode_for(output)
+            .ok_or(Pll5p49vError::InvalidModeForOperation)?;
+
+        self.set_output_mode(output, mode).await
+    }
+
+    /// Select the clock source of an output, e.g. to check the reference clock at bring-up
+    ///
+    /// Bypass is available on OUT1 only, other outputs return `InvalidModeForOperation`.
+    pub async fn set_output_mode(
+        &mut self,
+        output: OutputChannel,
+        mode: OutputMode,
+    ) -> Result<(), Pll5p49vError<E>> {
+        if mode == OutputMode::Bypass && output != OutputChannel::Out1 {
+            return Err(Pll5p49vError::InvalidModeForOperation);
+        }
+
+        self.read_modify_write(output.divider_control_reg(), OutputMode::MASK, mode.bits())
+            .await
+    }
+
+    /// Switch the reference to the crystal and set its load capacitance
+    ///
+    /// `load_cap_half_pf` is in 0.5 pF units, 16 - 40 (8 - 20 pF). X1 (0x12) and
+    /// X2 (0x13) get the same value, EN_XTAL is set and EN_CLKIN cleared (0x10).
+    /// `write_config` overwrites these registers, so call this afterwards.
+    pub async fn configure_input_crystal(
+        &mut self,
+        load_cap_half_pf: u8,
+    ) -> Result<(), Pll5p49vError<E>> {
+        if !(16..=40).contains(&load_cap_half_pf) {
+            return Err(Pll5p49vError::LoadCapOutOfRange);
+        }
+
+        // 8 pF at code 0, 0.5 pF per step
+        let bits = (load_cap_half_pf - 16) << 2;
+
+        self.read_modify_write(regs::XTAL_X1_LOAD_CAP, regs::XTAL_LOAD_CAP_MASK, bits)
+            .await?;
+
+        self.read_modify_write(
+            regs::XTAL_X2_LOAD_CAP,
+            regs::XTAL_LOAD_CAP_MASK | regs::XTAL_X2_LOAD_CAP_PRIMSRC,
+            bits,
+        )
+        .await?;
+
+        self.read_modify_write(
+            regs::PRIMARY_SRC_SHDN,
+            regs::PRIMARY_SRC_SHDN_EN_XTAL | regs::PRIMARY_SRC_SHDN_EN_CLKIN,
+            regs::PRIMARY_SRC_SHDN_EN_XTAL,
+        )
+        .await
+    }
+
+    /// Align the output phases by resetting all output dividers at once
+    ///
+    /// Pulses the global reset bit (register 0x76, bit 5, active low) for 10 us.
+    /// The PLL must be settled first (see `Pll5p49vConfig::stabilization_ms`),
+    /// otherwise the dividers restart on an unsettled VCO clock.
+    pub async fn sync_outputs(&mut self) -> Result<(), Pll5p49vError<E>> {
+        self.read_modify_write(regs::GLOBAL_CTRL, regs::GLOBAL_CTRL_RESET_N, 0)
+            .await?;
+
+        Timer::after_micros(10).await;
+
+        self.read_modify_write(
+            regs::GLOBAL_CTRL,
+            regs::GLOBAL_CTRL_RESET_N,
+            regs::GLOBAL_CTRL_RESET_N,
+        )
+        .await
+    }
+
+    /// Apply the settings of all four outputs, `configs` in output order
+    ///
+    /// Each output gets its format first, then the drive strength (LVCMOS
+    /// only), slew rate (where the format has one), polarity and enable, the
+    /// same fields `write_config` takes from `Pll5p49vConfig::clock_outputs`.
+    /// Stops at the first failure, earlier outputs stay configured.
+    pub async fn configure_all_outputs(
+        &mut self,
+        configs: [ClockOutputConfig; 4],
+    ) -> Result<(), Pll5p49vError<E>> {
+        for (output, config) in OutputChannel::iter().zip(configs) {
+            self.set_output_format(output, config.format).await?;
+
+            if config.format == OutputFormat::Lvcmos {
+                self.set_output_drive_strength(output, config.drive_strength)
+                    .await?;
+            }
+
+            if config.slew_rate.field(config.format).is_some() {
+                self.set_output_slew_rate(output, config.slew_rate).await?;
+            }
+
+            self.set_output_polarity(output, config.polarity == Polarity::Inverted)
+                .await?;
+
+            self.enable_output(output, config.enabled).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Reprogram the divider of one output, the other outputs are not touched
+    ///
+    /// `vco_fq_hz` must be the VCO frequency the chip runs at. Writes the
+    /// divider control register and the divider block (0xX1 - 0xXE) in one
+    /// burst; spread spectrum of this output is turned off.
+    pub async fn set_output_frequency(
+        &mut self,
+        output: OutputChannel,
+        vco_fq_hz: u32,
+        desired_fq_hz: u32,
+    ) -> Result<(), Pll5p49vError<E>> {
+        let out_of_range = ConfigError::OutputDividerOutOfRange {
+            channel: output.index(),
+            requested_hz: desired_fq_hz,
+        };
+
+        if desired_fq_hz == 0 {
+            return Err(out_of_range.into());
+        }
+
+        let od = compute_output_divider(vco_fq_hz, desired_fq_hz);
+
+        if od >> 32 == 0 || od >> 32 > 0xFFF {
+            return Err(out_of_range.into());
+        }
+
+        let control = self.read_register(output.divider_control_reg()).await?;
+
+        let mode = OutputDividerMode::detect(od, SpreadSpectrum::Disabled);
+
+        let mut block = [0u8; 14];
+        block[0] = (control & !regs::OD_CONTROL_INT_MODE) | mode.control_bits();
+        let mut bank = RegisterBank::DEFAULTS;
+        bank.set_od(output.index(), od);
+        block[1..].copy_from_slice(&bank.od_block(output.index()));
+
+        self.write_registers(output.divider_control_reg(), &block)
+            .await
+    }
+
+    /// Shift an output frequency by `delta_ppm` through the divider fraction
+    ///
+    /// Only the control and fraction registers (0xX1 - 0xX5) are written, the
+    /// integer part is kept, so the shift is clamped at the ends of the
+    /// fraction range. INT_MODE in the control register follows the trimmed
+    /// fraction like in `set_output_frequency`. Returns the shift actually
+    /// applied, ppm. Fails with `InvalidModeForOperation` on an output divider
+    /// without an integer part.
+    pub async fn trim_output_frequency(
+        &mut self,
+        output: OutputChannel,
+        delta_ppm: i32,
+    ) -> Result<i32, Pll5p49vError<E>> {
+        let mut block = [0u8; 14];
+        self.read_registers(output.divider_control_reg(), &mut block)
+            .await?;
+
+        let control = block[0];
+        let mut od_regs = [0u8; 13];
+        od_regs.copy_from_slice(&block[1..]);
+
+        let od = unpack_output_divider(&od_regs);
+        let integer = od >> 32;
+
+        if integer == 0 {
+            return Err(Pll5p49vError::InvalidModeForOperation);
+        }
+
+        // f = VCO / (2 * OD): a higher frequency needs a smaller divider,
+        // the chip keeps bits 31:8 of the 32-bit fraction
+        let min_od = integer << 32;
+        let max_od = min_od | 0xFFFFFF00;
+
+        let denominator = 1000000 + delta_ppm as i64;
+
+        let trimmed = if denominator <= 0 {
+            max_od
+        } else {
+            let target = (od as u128 * 1000000) / denominator as u128;
+
+            target.clamp(min_od as u128, max_od as u128) as u64 & !0xFF
+        };
+
+        let fraction = trimmed as u32;
+
+        // A spread spectrum modulated divider stays fractional
+        let mode = if od_regs[3] & regs::OD_FRAC_3_SPREAD_EN != 0 {
+            OutputDividerMode::Fractional
+        } else {
+            OutputDividerMode::detect(trimmed, SpreadSpectrum::Disabled)
+        };
+
+        let fraction_regs = [
+            (control & !regs::OD_CONTROL_INT_MODE) | mode.control_bits(),
+            (od_regs[0] & !0b11) | (fraction >> 30) as u8,
+            (fraction >> 22) as u8,
+            (fraction >> 14) as u8,
+            (od_regs[3] & 0b11) | ((fraction >> 6) as u8 & 0b11111100),
+        ];
+
+        self.write_registers(output.divider_control_reg(), &fraction_regs)
+            .await?;
+
+        Ok(((od as i128 - trimmed as i128) * 1000000 / trimmed as i128) as i32)
+    }
+}
+
+impl<I2C, E, L: PllLogger, O: OutputPin> Pll5p49v<I2C, Gen5, Configured, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// No-op: 5th generation devices calibrate the VCO automatically after programming
+    pub async fn calibrate_vco(
+        self,
+    ) -> Result<Pll5p49v<I2C, Gen5, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        Ok(self.into_state())
+    }
+
+    /// Same as `calibrate_vco`, the delay is not used
+    pub async fn calibrate_vco_with_delay<D: embedded_hal_async::delay::DelayNs>(
+        self,
+        _delay: &mut D,
+    ) -> Result<Pll5p49v<I2C, Gen5, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        Ok(self.into_state())
+    }
+
+    /// Same as `calibrate_vco`, there is nothing to retry
+    pub async fn calibrate_vco_with_retries(
+        self,
+        _max_retries: u8,
+    ) -> Result<Pll5p49v<I2C, Gen5, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        Ok(self.into_state())
+    }
+
+    /// Same as `calibrate_vco`, the timing is not used
+    pub async fn calibrate_vco_with_params(
+        self,
+        _params: &PllCalibrationParams,
+    ) -> Result<Pll5p49v<I2C, Gen5, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        Ok(self.into_state())
+    }
+}
+
+impl<I2C, E, L: PllLogger, O: OutputPin> Pll5p49v<I2C, Gen6, Configured, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Calibrate VCO, required after programming on 6th generation devices
+    ///
+    /// Waits with `embassy_time`, see `calibrate_vco_with_delay` for other timers.
+    /// Retries up to `DEFAULT_CALIBRATION_RETRIES` times, see `calibrate_vco_with_retries`.
+    pub async fn calibrate_vco(
+        self,
+    ) -> Result<Pll5p49v<I2C, Gen6, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        self.calibrate_vco_with_delay(&mut embassy_time::Delay)
+            .await
+    }
+
+    /// Calibrate VCO using the given delay provider for the 1 ms waits
+    pub async fn calibrate_vco_with_delay<D: embedded_hal_async::delay::DelayNs>(
+        mut self,
+        delay: &mut D,
+    ) -> Result<Pll5p49v<I2C, Gen6, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        match self
+            .run_calibration(
+                delay,
+                &PllCalibrationParams::DEFAULT,
+                DEFAULT_CALIBRATION_RETRIES,
+            )
+            .await
+        {
+            Ok(_) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Calibrate VCO, repeating the calibration while the VCO index is unstable
+    ///
+    /// After each calibration the index (register 0x99) is read twice 1 ms
+    /// apart. A differing index triggers another calibration, after
+    /// `max_retries` repetitions the result is `CalibrationUnstable`.
+    pub async fn calibrate_vco_with_retries(
+        mut self,
+        max_retries: u8,
+    ) -> Result<Pll5p49v<I2C, Gen6, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        match self
+            .run_calibration(
+                &mut embassy_time::Delay,
+                &PllCalibrationParams::DEFAULT,
+                max_retries,
+            )
+            .await
+        {
+            Ok(_) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+
+    /// Calibrate VCO with the given pulse timing instead of the 1 ms default
+    pub async fn calibrate_vco_with_params(
+        mut self,
+        params: &PllCalibrationParams,
+    ) -> Result<Pll5p49v<I2C, Gen6, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        match self
+            .run_calibration(
+                &mut embassy_time::Delay,
+                params,
+                DEFAULT_CALIBRATION_RETRIES,
+            )
+            .await
+        {
+            Ok(_) => Ok(self.into_state()),
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<I2C, E, S: Initialized, L: PllLogger, O: OutputPin> Pll5p49v<I2C, Gen6, S, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    async fn run_calibration<D: embedded_hal_async::delay::DelayNs>(
+        &mut self,
+        delay: &mut D,
+        params: &PllCalibrationParams,
+        max_retries: u8,
+    ) -> Result<u8, Pll5p49vError<E>> {
+        let mut last_index = 0;
+
+        for _ in 0..=max_retries {
+            // Write 0-1-0 to the 7th bit of the register 0x1C
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, 0)
+                .await?;
+
+            delay.delay_us(params.reset_assert_us).await;
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, regs::CAL_START_BIT)
+                .await?;
+
+            delay.delay_us(params.reset_hold_us).await;
+
+            self.read_modify_write(regs::CAL_START, regs::CAL_START_BIT, 0)
+                .await?;
+
+            delay.delay_ms(params.post_cal_settle_ms).await;
+
+            let vco_index = self.read_vco_index().await?;
+
+            delay.delay_ms(1).await;
+
+            last_index = self.read_vco_index().await?;
+
+            if vco_index == last_index {
+                self.logger.log_vco_index(vco_index);
+
+                return Ok(vco_index);
+            }
+        }
+
+        Err(Pll5p49vError::CalibrationUnstable {
+            retries: max_retries,
+            last_index,
+        })
+    }
+
+    /// Change to another configuration with the outputs held off, e.g. between
+    /// the 44.1 kHz and 48 kHz audio clock families
+    ///
+    /// Disables OUT1..OUT4 (register 0x68), writes `new_config` with the
+    /// outputs still disabled, calibrates the VCO, waits
+    /// `Pll5p49vConfig::stabilization_ms` and enables the outputs of
+    /// `new_config`. The chip has no lock-detect bit, so the wait is not
+    /// confirmed by a status read. The configuration is checked before
+    /// anything is written. A failing step leaves the outputs disabled and
+    /// returns the driver in its previous state. The duration is reported by
+    /// `PllLogger::log_plan_switch`.
+    pub async fn switch_frequency_plan(
+        mut self,
+        new_config: &Pll5p49vConfig,
+    ) -> Result<Pll5p49v<I2C, Gen6, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        let start = embassy_time::Instant::now();
+
+        let result = async {
+            let output_en = self.write_with_outputs_held(new_config).await?;
+
+            self.run_calibration(
+                &mut embassy_time::Delay,
+                &PllCalibrationParams::DEFAULT,
+                DEFAULT_CALIBRATION_RETRIES,
+            )
+            .await?;
+
+            self.release_outputs(new_config, output_en).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.logger.log_plan_switch(start.elapsed().as_micros());
+
+                Ok(self.into_state())
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+impl<I2C, E, S: Initialized, L: PllLogger, O: OutputPin> Pll5p49v<I2C, Gen5, S, L, O>
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    /// Change to another configuration with the outputs held off, see the
+    /// 6th generation `switch_frequency_plan`
+    ///
+    /// The same steps without the calibration, 5th generation devices
+    /// calibrate the VCO after programming by themselves.
+    pub async fn switch_frequency_plan(
+        mut self,
+        new_config: &Pll5p49vConfig,
+    ) -> Result<Pll5p49v<I2C, Gen5, Calibrated, L, O>, (Self, Pll5p49vError<E>)> {
+        let start = embassy_time::Instant::now();
+
+        let result = async {
+            let output_en = self.write_with_outputs_held(new_config).await?;
+
+            self.release_outputs(new_config, output_en).await
+        }
+        .await;
+
+        match result {
+            Ok(()) => {
+                self.logger.log_plan_switch(start.elapsed().as_micros());
+
+                Ok(self.into_state())
+            }
+            Err(e) => Err((self, e)),
+        }
+    }
+}
+
+/// Check the chip every `interval_ms` and reprogram it when it lost `config`
+///
+/// The 5P49V has no lock-detect bit, so the check reads back the dividers
+/// (see `verify_config`): a brownout or a glitch on the supply resets the
+/// registers to their power-up values. On a mismatch the full configuration
+/// is written again and the VCO calibrated. Failed attempts, e.g. bus errors,
+/// are retried on the next check. To share the bus with other devices, build
+/// the driver on an `embassy_embedded_hal` shared bus `I2cDevice` over a
+/// `Mutex`.
+pub async fn pll_monitor_task<I2C, E, L: PllLogger, O: OutputPin>(
+    pll: &mut Pll5p49v<I2C, Gen6, Calibrated, L, O>,
+    config: &Pll5p49vConfig,
+    interval_ms: u32,
+) -> !
+where
+    I2C: embedded_hal_async::i2c::I2c<Error = E>,
+{
+    loop {
+        Timer::after_millis(interval_ms as u64).await;
+
+        if !matches!(
+            pll.verify_config(config).await,
+            Err(Pll5p49vError::Mismatch { .. })
+        ) {
+            continue;
+        }
+
+        pll.logger.log_configuration_lost();
+
+        // The cache describes the registers before the loss, write all of them
+        pll.invalidate_cache();
+
+        // Stays `Calibrated`: a failed attempt is repeated on the next check
+        if pll.write_prog_array(config).await.is_ok() {
+            let _ = pll
+                .run_calibration(
+                    &mut embassy_time::Delay,
+                    &PllCalibrationParams::DEFAULT,
+                    DEFAULT_CALIBRATION_RETRIES,
+                )
+                .await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Register value in a configuration burst, which starts with the address
+    fn reg(prog_array: &[u8; PROG_ARRAY_SIZE], reg: u8) -> u8 {
+        prog_array[reg as usize + 1]
+    }
+
+    /// Register map as read back from a chip programmed with `config`
+    fn register_dump(config: &Pll5p49vConfig) -> [u8; REGISTER_MAP_SIZE] {
+        let prog_array = build_prog_array(config).unwrap();
+
+        let mut dump = [0u8; REGISTER_MAP_SIZE];
+        dump[..CONFIG_REGISTER_COUNT].copy_from_slice(&prog_array[1..]);
+        dump
+    }
+
+    include!("../tests/common/golden.rs");
+
+    #[test]
+    fn default_6965_burst() {
+        assert_eq!(
+            build_prog_array(&DEFAULT_CONFIG_6965).unwrap(),
+            GOLDEN_6965_BURST
+        );
+    }
+
+    #[test]
+    fn feedback_divider_integer() {
+        // 2700 MHz / 10 MHz = 270 = 0x10E
+        let prog_array = build_prog_array(&DEFAULT_CONFIG_6965).unwrap();
+
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_HI), 0x10);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_LO), 0xE0);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_0), 0x00);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_1), 0x00);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_2), 0x00);
+    }
+
+    #[test]
+    fn feedback_divider_fractional() {
+        // 2700 MHz / 24 MHz = 112.5, the sigma-delta modulator runs 3rd order
+        let config = Pll5p49vConfig {
+            clock_fq_hz: 24000000,
+            ..DEFAULT_CONFIG_6965
+        };
+        let prog_array = build_prog_array(&config).unwrap();
+
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_HI), 0x07);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_LO), 0x0C);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_0), 0x80);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_1), 0x00);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_FRAC_2), 0x00);
+    }
+
+    #[test]
+    fn feedback_divider_bytes() {
+        // (clock, VCO, registers 0x17 - 0x1B), the fractional ones with the
+        // 3rd order sigma-delta modulator in 0x18 bits 3:2
+        let cases = [
+            (10000000, 2700000000, [0x10, 0xE0, 0x00, 0x00, 0x00]), // 270
+            (24000000, 2700000000, [0x07, 0x0C, 0x80, 0x00, 0x00]), // 112.5
+            (25000000, 2500000000, [0x06, 0x40, 0x00, 0x00, 0x00]), // 100
+            (27000000, 2600000000, [0x06, 0x0C, 0x4B, 0xDA, 0x12]), // 96.296
+            (38400000, 3000000000, [0x04, 0xEC, 0x20, 0x00, 0x00]), // 78.125
+        ];
+
+        for (clock_fq_hz, vco_fq_hz, expected) in cases {
+            let config = Pll5p49vConfig {
+                clock_fq_hz,
+                vco_fq_hz,
+                ..DEFAULT_CONFIG_6965
+            };
+            let prog_array = build_prog_array(&config).unwrap();
+
+            assert_eq!(prog_array[24..29], expected, "{clock_fq_hz} Hz");
+        }
+    }
+
+    #[test]
+    fn feedback_divider_fraction_lsb_dropped() {
+        // 2600 MHz / 27 MHz = 0x60_4BDA12F6, the fraction field (0x19 - 0x1B)
+        // holds bits 31:8, 0xF6 has no register
+        let config = Pll5p49vConfig {
+            clock_fq_hz: 27000000,
+            vco_fq_hz: 2600000000,
+            ..DEFAULT_CONFIG_6965
+        };
+        let feedback_divider = compute_feedback_divider(config.vco_fq_hz, config.clock_fq_hz);
+
+        assert_eq!(feedback_divider & 0xFF, 0xF6);
+
+        let dump = register_dump(&config);
+        let mut feedback_regs = [0u8; 5];
+        feedback_regs.copy_from_slice(
+            &dump[regs::FEEDBACK_DIV_INT_HI as usize..=regs::FEEDBACK_DIV_FRAC_2 as usize],
+        );
+
+        assert_eq!(
+            unpack_feedback_divider(&feedback_regs),
+            feedback_divider & !0xFF
+        );
+
+        // The truncation costs 2 Hz of the VCO frequency read back
+        let read_back = Pll5p49vConfig::from_registers(&dump, config.clock_fq_hz).unwrap();
+
+        assert_eq!(read_back.vco_fq_hz, 2599999998);
+    }
+
+    #[test]
+    fn feedback_divider_12_bit_limit() {
+        // 3000 MHz / 1 MHz = 3000 = 0xBB8 fits the 12-bit integer field
+        let config = Pll5p49vConfig {
+            clock_fq_hz: 1000000,
+            vco_fq_hz: 3000000000,
+            outputs: [Some(100000000), None, None, None],
+            ..DEFAULT_CONFIG_6965
+        };
+        let prog_array = build_prog_array(&config).unwrap();
+
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_HI), 0xBB);
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_LO), 0x80);
+
+        // 3000 MHz / 0.5 MHz = 6000 does not
+        let config = Pll5p49vConfig {
+            clock_fq_hz: 500000,
+            ..config
+        };
+
+        assert_eq!(
+            build_prog_array(&config),
+            Err(ConfigError::FeedbackDividerOverflow)
+        );
+    }
+
+    #[test]
+    fn output_divider_fields() {
+        let prog_array = build_prog_array(&DEFAULT_CONFIG_6965).unwrap();
+
+        // (integer, fraction bits 31:30) of 33.75, 54, 56.25 and 46.875
+        let expected = [(33, 0b11), (54, 0b00), (56, 0b01), (46, 0b11)];
+
+        for (output, (integer, fraction)) in OutputChannel::iter().zip(expected) {
+            let control = output.divider_control_reg();
+
+            assert_eq!(reg(&prog_array, control + 1) & 0b11, fraction);
+            assert_eq!(reg(&prog_array, control + 12), (integer >> 4) as u8);
+            assert_eq!(reg(&prog_array, control + 13), (integer << 4) as u8);
+        }
+
+        // 46.875: fraction 0.875 = 0xE0000000, bits 29:22 in 0x53
+        assert_eq!(reg(&prog_array, 0x53), 0x80);
+    }
+
+    #[test]
+    fn output_divider_out_of_range() {
+        // 2700 MHz / (2 * 100 Hz) = 13500000 does not fit the 12-bit field
+        let config = Pll5p49vConfig {
+            outputs: [Some(40000000), Some(100), None, None],
+            ..DEFAULT_CONFIG_6965
+        };
+
+        assert_eq!(
+            build_prog_array(&config),
+            Err(ConfigError::OutputDividerOutOfRange {
+                channel: 1,
+                requested_hz: 100
+            })
+        );
+    }
+
+    #[test]
+    fn integer_output_divider_mode() {
+        let prog_array = build_prog_array(&DEFAULT_CONFIG_6965).unwrap();
+
+        // OUT2 runs at 2700 MHz / (2 * 54)
+        assert_ne!(
+            reg(&prog_array, regs::OD2_CONTROL) & regs::OD_CONTROL_INT_MODE,
+            0
+        );
+        assert_eq!(
+            reg(&prog_array, regs::OD1_CONTROL) & regs::OD_CONTROL_INT_MODE,
+            0
+        );
+    }
+
+    #[test]
+    fn sigma_delta_bypass_for_integer_feedback() {
+        let prog_array = build_prog_array(&DEFAULT_CONFIG_6965).unwrap();
+
+        assert_eq!(reg(&prog_array, regs::FEEDBACK_DIV_INT_LO) & 0x0C, 0);
+
+        let config = Pll5p49vConfig {
+            clock_fq_hz: 24000000,
+            sigma_delta_order: SigmaDeltaOrder::Bypass,
+            ..DEFAULT_CONFIG_6965
+        };
+
+        assert_eq!(
+            build_prog_array(&config),
+            Err(ConfigError::SigmaDeltaRequiredForFractional)
+        );
+    }
+
+    #[test]
+    fn build_prog_array_edge_inputs() {
+        // Returns a burst or a `ConfigError` for any frequency, never panics
+        let frequencies = [
+            0,
+            1,
+            2,
+            1000,
+            10000000,
+            350000000,
+            2700000000,
+            u32::MAX / 2,
+            u32::MAX - 1,
+            u32::MAX,
+        ];
+        let spreads = [
+            SpreadSpectrum::Disabled,
+            SpreadSpectrum::CenterSpread {
+                deviation_ppm: u16::MAX,
+            },
+            SpreadSpectrum::DownSpread { deviation_ppm: 1 },
+        ];
+
+        let mut built = 0;
+        let mut round_trips = 0;
+
+        for clock_fq_hz in frequencies {
+            for vco_fq_hz in frequencies {
+                for output_fq_hz in frequencies {
+                    for spread_spectrum in spreads {
+                        let config = Pll5p49vConfig {
+                            clock_fq_hz,
+                            vco_fq_hz,
+                            outputs: [Some(output_fq_hz), None, Some(output_fq_hz), None],
+                            spread_spectrum,
+                            ..DEFAULT_CONFIG_6965
+                        };
+
+                        let result = std::panic::catch_unwind(|| build_prog_array(&config));
+
+                        let Ok(Ok(prog_array)) = result else {
+                            assert!(result.is_ok(), "panic for {config:?}");
+                            continue;
+                        };
+
+                        // The burst reads back as a configuration. Without
+                        // spread (decoded as a span only) and with an integer
+                        // feedback divider it gives the same burst
+                        let mut dump = [0u8; REGISTER_MAP_SIZE];
+                        dump[..CONFIG_REGISTER_COUNT].copy_from_slice(&prog_array[1..]);
+
+                        let parsed = Pll5p49vConfig::from_registers(&dump, clock_fq_hz)
+                            .unwrap_or_else(|e| panic!("{e:?} for {config:?}"));
+
+                        let feedback_divider = compute_feedback_divider(vco_fq_hz, clock_fq_hz);
+
+                        assert_eq!(
+                            parsed.outputs.map(|o| o.is_some()),
+                            [true, false, true, false]
+                        );
+
+                        if spread_spectrum == SpreadSpectrum::Disabled
+                            && feedback_divider & 0xFFFFFFFF == 0
+                        {
+                            assert_eq!(register_dump(&parsed), dump, "{config:?}");
+                            round_trips += 1;
+                        }
+
+                        built += 1;
+                    }
+                }
+            }
+        }
+
+        // Some of the combinations get through to the register packing
+        assert!(built > 0);
+        assert!(round_trips > 0);
+    }
+
+    #[test]
+    fn vco_band_byte() {
+        let prog_array = build_prog_array(&DEFAULT_CONFIG_6965).unwrap();
+
+        assert_eq!(reg(&prog_array, regs::VCO_BAND), 0x0D);
+
+        let config = Pll5p49vConfig::builder()
+            .vco_band(Some(0x12))
+            .build()
+            .unwrap();
+        let prog_array = build_prog_array(&config).unwrap();
+
+        assert_eq!(
+            reg(&prog_array, regs::VCO_BAND),
+            regs::VCO_BAND_TEST_MODE | 0x12
+        );
+    }
+
+    #[test]
+    fn output_divider_math() {
+        assert_eq!(
+            compute_output_divider(2700000000, 40000000),
+            (33 << 32) | 0xC0000000
+        );
+        assert_eq!(
+            compute_feedback_divider(2700000000, 24000000),
+            (112 << 32) | 0x80000000
+        );
+
+        let od = compute_output_divider(2700000000, 28800000);
+
+        assert_eq!(compute_actual_frequency(2700000000, od), 28800000);
+        assert_eq!(frequency_error_ppm(28800000, 2700000000, od), 0);
+        assert_eq!(compute_actual_frequency(2700000000, 0), 0);
+    }
+
+    #[test]
+    fn frequency_error() {
+        // Divider for 40.004 MHz, 100 ppm above the requested 40 MHz
+        let od = compute_output_divider(2700000000, 40004000);
+        let error_ppm = frequency_error_ppm(40000000, 2700000000, od);
+
+        assert!((99..=100).contains(&error_ppm), "{error_ppm}");
+
+        // 7 MHz is OD 1350 / 7, the register holds a rounded fraction. The
+        // resulting error, about 1.9 mHz or 0.26 ppb, is below the Hz and ppm
+        // resolution of the plan
+        let od = compute_output_divider(2700000000, 7000000) & !0xFF;
+
+        assert_ne!(od * 7 * 2, 2700000000u64 << 32);
+
+        let actual_nhz = ((2700000000u128 << 31) * 1000000000) / od as u128;
+
+        assert_eq!(actual_nhz - 7000000000000000, 1854366);
+
+        let plan = PllFrequencyPlan::from_config(Pll5p49vConfig {
+            outputs: [Some(7000000), None, None, None],
+            ..DEFAULT_CONFIG_6965
+        });
+
+        assert_eq!(plan.actual_output_hz, [7000000, 0, 0, 0]);
+        assert_eq!(plan.error_ppm, [0; 4]);
+    }
+
+    #[test]
+    fn closest_achievable() {
+        // 100 MHz is OD 13.5, 7 MHz (OD 192.857...) snaps to OD 192
+        let config = Pll5p49vConfig {
+            outputs: [Some(100000000), Some(7000000), None, None],
+            ..DEFAULT_CONFIG_6965
+        };
+
+        let (adjusted, error_ppb) = config.closest_achievable().unwrap();
+
+        assert_eq!(
+            adjusted.outputs,
+            [Some(100000000), Some(7031250), None, None]
+        );
+        assert_eq!(error_ppb, [0, 4464285, 0, 0]);
+
+        let od = compute_output_divider(adjusted.vco_fq_hz, 7031250);
+
+        assert_eq!(od, 192 << 32);
+    }
+
+    #[test]
+    fn preset_for_usb3() {
+        let config = Pll5p49vConfig::for_usb3(25000000).unwrap();
+        let parsed = Pll5p49vConfig::from_registers(&register_dump(&config), 25000000).unwrap();
+
+        assert_eq!(parsed.outputs, [Some(100000000), None, None, None]);
+        assert_eq!(parsed.clock_outputs[0].format, OutputFormat::Hcsl);
+        assert!(matches!(
+            parsed.spread_spectrum,
+            SpreadSpectrum::DownSpread { .. }
+        ));
+    }
+
+    #[test]
+    fn preset_for_ethernet() {
+        let expected = [
+            (
+                EthernetPhyInterface::Rmii25MHz,
+                25000000,
+                OutputFormat::Lvcmos,
+            ),
+            (
+                EthernetPhyInterface::Rgmii125MHz,
+                125000000,
+                OutputFormat::Lvcmos,
+            ),
+            (
+                EthernetPhyInterface::Sgmii125MHz,
+                125000000,
+                OutputFormat::Lvds,
+            ),
+        ];
+
+        for (phy_interface, output_fq_hz, format) in expected {
+            let config = Pll5p49vConfig::for_ethernet(25000000, phy_interface).unwrap();
+            let dump = register_dump(&config);
+            let parsed = Pll5p49vConfig::from_registers(&dump, 25000000).unwrap();
+
+            assert_eq!(parsed.outputs, [Some(output_fq_hz), None, None, None]);
+            assert_eq!(parsed.clock_outputs[0].format, format);
+            assert_eq!(parsed.global_output.voltage, OutputVoltage::V33);
+            assert_eq!(parsed.spread_spectrum, SpreadSpectrum::Disabled);
+        }
+    }
+
+    #[test]
+    fn preset_auto() {
+        let config = Pll5p49vConfig::auto(25000000, &[100000000, 33333333]).unwrap();
+        let output_en = register_dump(&config)[regs::OUTPUT_EN as usize];
+
+        assert_eq!(config.outputs[2..], [None, None]);
+        assert_eq!(
+            output_en & regs::OUTPUT_EN_MASK,
+            OutputChannel::Out1.enable_mask() | OutputChannel::Out2.enable_mask()
+        );
+        assert_eq!(PllFrequencyPlan::from_config(config).error_ppm, [0; 4]);
+
+        assert_eq!(
+            Pll5p49vConfig::auto(25000000, &[]),
+            Err(ConfigError::OutputCountOutOfRange)
+        );
+    }
+
+    #[test]
+    fn preset_optimal_vco() {
+        let outputs = [100000000, 125000000, 50000000, 25000000];
+        let vco_fq_hz = Pll5p49vConfig::optimal_vco(25000000, outputs).unwrap();
+
+        let config = Pll5p49vConfig::builder()
+            .clock(25000000)
+            .vco(vco_fq_hz)
+            .output1(outputs[0])
+            .output2(outputs[1])
+            .output3(outputs[2])
+            .output4(outputs[3])
+            .build()
+            .unwrap();
+
+        assert!(build_prog_array(&config).is_ok());
+        assert_eq!(PllFrequencyPlan::from_config(config).error_ppm, [0; 4]);
+    }
+
+    #[test]
+    fn variant_features() {
+        // Both features need a known variant supporting them
+        let builder = || Pll5p49vConfig::builder().enable_ref_out(Some(OutputChannel::Out1));
+
+        assert_eq!(
+            builder().build(),
+            Err(ConfigError::FeatureUnsupportedOnVariant)
+        );
+
+        let variant = ChipVariantInfo::for_part("5P49V6965").unwrap();
+
+        assert!(builder().variant(variant).build().is_ok());
+        assert_eq!(
+            builder().variant(variant).vco_monitor(true).build(),
+            Err(ConfigError::FeatureUnsupportedOnVariant)
+        );
+
+        static NO_REF_OUT: ChipVariantInfo =
+            ChipVariantInfo::new("5P49V6901", Generation::Sixth, true, false);
+
+        assert_eq!(
+            builder().variant(&NO_REF_OUT).build(),
+            Err(ConfigError::FeatureUnsupportedOnVariant)
+        );
+
+        let variant = ChipVariantInfo::for_part("5P49V6901").unwrap();
+
+        assert!(builder().variant(variant).vco_monitor(true).build().is_ok());
+    }
+
+    #[test]
+    fn lvds_at_1v8() {
+        let mut lvds = ClockOutputConfig::DEFAULT;
+        lvds.format = OutputFormat::Lvds;
+
+        let builder = || Pll5p49vConfig::builder().clock_output(OutputChannel::Out3, lvds);
+        let v18 = GlobalOutputConfig {
+            voltage: OutputVoltage::V18,
+        };
+
+        assert!(builder().build().is_ok());
+        assert_eq!(
+            builder().global_output(v18).build(),
+            Err(ConfigError::LvdsAt1v8 { channel: 2 })
+        );
+
+        // A configuration built by hand is refused when it is written
+        let mut config = DEFAULT_CONFIG_6965;
+        config.clock_outputs[2] = lvds;
+        config.global_output = v18;
+
+        assert_eq!(
+            build_prog_array(&config),
+            Err(ConfigError::LvdsAt1v8 { channel: 2 })
+        );
+    }
+
+    #[test]
+    fn output_enables() {
+        // OUT2 has no frequency, OUT3 is switched off
+        let mut config = DEFAULT_CONFIG_6965.with_output_disabled(OutputChannel::Out2);
+        config.clock_outputs[2].enabled = false;
+
+        let output_en = register_dump(&config)[regs::OUTPUT_EN as usize];
+
+        assert_eq!(
+            output_en & regs::OUTPUT_EN_MASK,
+            OutputChannel::Out1.enable_mask() | OutputChannel::Out4.enable_mask()
+        );
+    }
+
+    #[test]
+    fn config_equality() {
+        let build = || {
+            Pll5p49vConfig::builder()
+                .clock(25000000)
+                .vco(2500000000)
+                .output1(125000000)
+                .output2(25000000)
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(build(), build());
+
+        // A nested field differs
+        let mut other = build();
+        other.clock_outputs[1].drive_strength = DriveStrength::Ma16;
+
+        assert_ne!(build(), other);
+    }
+
+    #[test]
+    fn diff_from_disabled_output() {
+        let a = DEFAULT_CONFIG_6965.with_output_disabled(OutputChannel::Out2);
+        let diff = Pll5p49vConfig::diff(&a, &DEFAULT_CONFIG_6965).unwrap();
+
+        // All of OD2 (0x31 - 0x3F), whether or not the values differ
+        let od2: Vec<u8> = diff
+            .writes()
+            .iter()
+            .map(|&(reg, _)| reg)
+            .filter(|reg| (regs::OD2_CONTROL..regs::OD2_CONTROL + 15).contains(reg))
+            .collect();
+
+        assert_eq!(
+            od2,
+            (regs::OD2_CONTROL..regs::OD2_CONTROL + 15).collect::<Vec<_>>()
+        );
+
+        // Disabling an output again writes none of its divider block
+        let diff = Pll5p49vConfig::diff(&DEFAULT_CONFIG_6965, &a).unwrap();
+
+        assert!(diff
+            .writes()
+            .iter()
+            .all(|&(reg, _)| !(regs::OD2_CONTROL + 1..regs::OD2_CONTROL + 14).contains(&reg)));
+    }
+
+    #[test]
+    fn bytes_round_trip() {
+        let config = Pll5p49vConfig {
+            outputs: [Some(40000000), None, Some(24000000), Some(28800000)],
+            ..DEFAULT_CONFIG_6965
+        };
+
+        assert_eq!(Pll5p49vConfig::from_bytes(&config.to_bytes()), Ok(config));
+    }
+
+    #[test]
+    fn bytes_checksum_mismatch() {
+        let mut bytes = DEFAULT_CONFIG_6965.to_bytes();
+        bytes[4] ^= 0x01;
+
+        assert_eq!(
+            Pll5p49vConfig::from_bytes(&bytes),
+            Err(ParseError::ChecksumMismatch)
+        );
+    }
+
+    #[test]
+    fn bytes_erased() {
+        // Sums to zero like a stored configuration
+        assert_eq!(
+            Pll5p49vConfig::from_bytes(&[0; CONFIG_BYTES_SIZE]),
+            Err(ParseError::Config(ConfigError::ClockZero))
+        );
+    }
+
+    #[test]
+    fn registers_round_trip() {
+        let dump = register_dump(&DEFAULT_CONFIG_6965);
+        let config = Pll5p49vConfig::from_registers(&dump, 10000000).unwrap();
+
+        assert_eq!(config.vco_fq_hz, DEFAULT_CONFIG_6965.vco_fq_hz);
+        assert_eq!(config.outputs, DEFAULT_CONFIG_6965.outputs);
+        assert_eq!(config.clock_outputs, DEFAULT_CONFIG_6965.clock_outputs);
+        assert_eq!(config.global_output, DEFAULT_CONFIG_6965.global_output);
+
+        // `Auto` reads back as the order it selected
+        assert_eq!(config.sigma_delta_order, SigmaDeltaOrder::Bypass);
+        assert_eq!(register_dump(&config), dump);
+    }
+
+    #[test]
+    fn registers_round_trip_disabled_output() {
+        let dump = register_dump(&DEFAULT_CONFIG_6965.with_output_disabled(OutputChannel::Out3));
+        let config = Pll5p49vConfig::from_registers(&dump, 10000000).unwrap();
+
+        assert_eq!(config.outputs[2], None);
+        assert!(!config.clock_outputs[2].enabled);
+        assert_eq!(register_dump(&config), dump);
+    }
+
+    #[test]
+    fn registers_without_clock_source() {
+        let mut dump = register_dump(&DEFAULT_CONFIG_6965);
+        dump[regs::PRIMARY_SRC_SHDN as usize] = 0;
+
+        assert_eq!(
+            Pll5p49vConfig::from_registers(&dump, 10000000),
+            Err(ParseError::NoClockSource)
+        );
+    }
+
+    #[cfg(feature = "display")]
+    #[test]
+    fn display_config() {
+        let mut config = DEFAULT_CONFIG_6965.with_output_disabled(OutputChannel::Out4);
+        config.clock_outputs[1].format = OutputFormat::Lvds;
+
+        let text = std::format!("{}", config);
+
+        assert!(text.contains("OUT1: 40000000 Hz, LVCMOS 24 mA, fast slew, normal polarity\n"));
+        assert!(text.contains("OUT2: 25000000 Hz, LVDS, fast slew, normal polarity\n"));
+        assert!(text.contains("OUT4: disabled\n"));
+        assert!(text.contains("SD/OE pin: active high\n"));
+        assert!(text.ends_with("stabilization: 10 ms"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Register addresses and bits of the 5P49V
+//!
+//! Output divider registers are listed for OD1, the blocks of OD2..OD4 follow
+//! at `OD_STRIDE` intervals. Output configuration registers are listed for
+//! CLK1, CLK2..CLK4 follow at `CLK_CFG_STRIDE` intervals.
+
+/// Start address of the register map, first register of a full burst
+pub const START: u8 = 0x00;
+
+/// Device configuration, reads back non-zero on a responding chip
+pub const DEVICE_ID: u8 = 0x00;
+
+/// Reset control
+pub const RESET_CTRL: u8 = 0x01;
+/// Software reset, self-clearing
+pub const RESET_CTRL_SOFT_RESET: u8 = 0x80;
+
+/// Primary source and shutdown
+pub const PRIMARY_SRC_SHDN: u8 = 0x10;
+/// Crystal input enable
+pub const PRIMARY_SRC_SHDN_EN_XTAL: u8 = 0x80;
+/// CLKIN input enable
+pub const PRIMARY_SRC_SHDN_EN_CLKIN: u8 = 0x40;
+/// SD/OE pin polarity (SP), set for active low
+pub const PRIMARY_SRC_SHDN_SP: u8 = 0x02;
+/// SD/OE pin function (SH), set for shutdown instead of output enable
+pub const PRIMARY_SRC_SHDN_EN_GLOBAL_SHUTDOWN: u8 = 0x01;
+
+/// VCO band and factory reserved bits
+pub const VCO_BAND: u8 = 0x11;
+/// Use the VCO band field instead of the calibrated band
+pub const VCO_BAND_TEST_MODE: u8 = 0x20;
+/// VCO band field
+pub const VCO_BAND_MASK: u8 = 0x1F;
+
+/// Crystal X1 load capacitor
+pub const XTAL_X1_LOAD_CAP: u8 = 0x12;
+/// Load capacitance code in bits 7:2 of `XTAL_X1_LOAD_CAP` and `XTAL_X2_LOAD_CAP`
+pub const XTAL_LOAD_CAP_MASK: u8 = 0xFC;
+
+/// Crystal X2 load capacitor, holds the PRIMSRC bit
+pub const XTAL_X2_LOAD_CAP: u8 = 0x13;
+/// CLKIN is the primary source
+pub const XTAL_X2_LOAD_CAP_PRIMSRC: u8 = 0x02;
+
+/// Reference divider
+pub const REF_DIVIDER: u8 = 0x15;
+
+/// VCO control and pre-divider
+pub const VCO_CTRL_PREDIV: u8 = 0x16;
+
+/// Feedback divider integer, bits 11:4
+pub const FEEDBACK_DIV_INT_HI: u8 = 0x17;
+/// Feedback divider integer bits 3:0 (bits 7:4) and sigma-delta order (bits 3:2)
+pub const FEEDBACK_DIV_INT_LO: u8 = 0x18;
+/// Feedback divider fraction, bits 23:16
+pub const FEEDBACK_DIV_FRAC_0: u8 = 0x19;
+/// Feedback divider fraction, bits 15:8
+pub const FEEDBACK_DIV_FRAC_1: u8 = 0x1A;
+/// Feedback divider fraction, bits 7:0
+pub const FEEDBACK_DIV_FRAC_2: u8 = 0x1B;
+
+/// VCO calibration start and factory reserved bits
+pub const CAL_START: u8 = 0x1C;
+/// VCO calibration start bit
+pub const CAL_START_BIT: u8 = 0x80;
+
+/// VCO monitor and factory reserved bits
+pub const VCO_MONITOR: u8 = 0x1D;
+/// VCO monitor enable
+pub const VCO_MONITOR_EN: u8 = 0x02;
+
+/// RC control
+pub const RC_CONTROL_0: u8 = 0x1E;
+/// RC control
+pub const RC_CONTROL_1: u8 = 0x1F;
+
+/// Distance between the register blocks of two output dividers
+pub const OD_STRIDE: u8 = 0x10;
+
+/// OD1 control
+pub const OD1_CONTROL: u8 = 0x21;
+/// Output divider control: reset
+pub const OD_CONTROL_RESET: u8 = 0x80;
+/// Output divider control: select external (bypass / cascade) source
+pub const OD_CONTROL_SEL_EXT: u8 = 0x04;
+/// Output divider control: integer mode
+pub const OD_CONTROL_INT_MODE: u8 = 0x02;
+/// Output divider control: fractional output divider enable
+pub const OD_CONTROL_EN_FOD: u8 = 0x01;
+
+/// OD1 fraction, bits 23:22 (bits 1:0)
+pub const OD1_FRAC_0: u8 = 0x22;
+/// OD1 fraction, bits 21:14
+pub const OD1_FRAC_1: u8 = 0x23;
+/// OD1 fraction, bits 13:6
+pub const OD1_FRAC_2: u8 = 0x24;
+/// OD1 fraction, bits 5:0 (bits 7:2) and spread enable (bit 1)
+pub const OD1_FRAC_3: u8 = 0x25;
+/// Spread enable bit in `OD1_FRAC_3`
+pub const OD_FRAC_3_SPREAD_EN: u8 = 0x02;
+
+/// OD1 spread step, bits 23:16
+pub const OD1_SS_STEP_0: u8 = 0x26;
+/// OD1 spread step, bits 15:8
+pub const OD1_SS_STEP_1: u8 = 0x27;
+/// OD1 spread step, bits 7:0
+pub const OD1_SS_STEP_2: u8 = 0x28;
+/// OD1 spread period, bits 12:5
+pub const OD1_SS_PERIOD_0: u8 = 0x29;
+/// OD1 spread period, bits 4:0 (bits 7:3)
+pub const OD1_SS_PERIOD_1: u8 = 0x2A;
+
+/// OD1 integer skew
+pub const OD1_SKEW_INT_0: u8 = 0x2B;
+/// OD1 integer skew
+pub const OD1_SKEW_INT_1: u8 = 0x2C;
+
+/// OD1 integer, bits 11:4
+pub const OD1_INT_HI: u8 = 0x2D;
+/// OD1 integer, bits 3:0 (bits 7:4)
+pub const OD1_INT_LO: u8 = 0x2E;
+
+/// OD1 fractional skew
+pub const OD1_SKEW_FRAC: u8 = 0x2F;
+
+/// OD2 control
+pub const OD2_CONTROL: u8 = OD1_CONTROL + OD_STRIDE;
+/// OD3 control
+pub const OD3_CONTROL: u8 = OD1_CONTROL + 2 * OD_STRIDE;
+/// OD4 control
+pub const OD4_CONTROL: u8 = OD1_CONTROL + 3 * OD_STRIDE;
+
+/// Distance between the configuration registers of two outputs
+pub const CLK_CFG_STRIDE: u8 = 0x02;
+
+/// CLK1 output configuration: format, drive strength and slew rate
+pub const CLK1_CFG_0: u8 = 0x60;
+/// CLK1 output configuration: polarity
+pub const CLK1_CFG_1: u8 = 0x61;
+/// Normal (non-inverted) polarity in the second output configuration register
+pub const CLK_CFG_1_NORMAL_POLARITY: u8 = 0x01;
+/// CLK2 output configuration
+pub const CLK2_CFG_0: u8 = CLK1_CFG_0 + CLK_CFG_STRIDE;
+/// CLK3 output configuration
+pub const CLK3_CFG_0: u8 = CLK1_CFG_0 + 2 * CLK_CFG_STRIDE;
+/// CLK4 output configuration
+pub const CLK4_CFG_0: u8 = CLK1_CFG_0 + 3 * CLK_CFG_STRIDE;
+
+/// Output enable, bit 7 - OUT0 (reference output), bit 6 - OUT1 .. bit 3 - OUT4
+pub const OUTPUT_EN: u8 = 0x68;
+/// OUT0 enable bit, the buffered reference input on the OUT0_SEL_I2CB pin
+pub const OUTPUT_EN_OUT0_REF: u8 = 0x80;
+/// OUT1 enable bit, OUT2..OUT4 follow towards the LSB
+pub const OUTPUT_EN_OUT1: u8 = 0x40;
+/// OUT1..OUT4 enable bits
+pub const OUTPUT_EN_MASK: u8 = 0x78;
+
+/// Output control
+pub const OUTPUT_CTRL: u8 = 0x69;
+/// 1.8 V output voltage
+pub const OUTPUT_CTRL_V18: u8 = 0x02;
+
+/// Global control
+pub const GLOBAL_CTRL: u8 = 0x76;
+/// Global divider reset, active low: the output dividers restart together on release
+pub const GLOBAL_CTRL_RESET_N: u8 = 0x20;
+
+/// VCO status: calibrated band index (bits 7:3)
+pub const VCO_STATUS: u8 = 0x99;
@@ -0,0 +1,98 @@
+//! Frequency plan report for host-side tools, enabled by the `report` feature
+//!
+//! Lists every usable VCO frequency for a set of output targets, e.g. to
+//! compare reference clocks while choosing a crystal for a board. Uses
+//! `alloc`, the application has to provide a global allocator.
+
+use alloc::string::String;
+use core::fmt::{self, Write};
+
+use crate::{
+    compute_actual_frequency, compute_feedback_divider, compute_output_divider,
+    frequency_error_ppm, vco_frequency,
+};
+
+/// Step between the VCO candidates of `generate_frequency_plan_report`, Hz
+const VCO_STEP_HZ: u32 = 1000000;
+
+/// Markdown table of the VCO candidates in `vco_range` (min, max) in 1 MHz steps
+///
+/// One row per usable candidate with the actual frequency and error of every
+/// output target, as programmed by `write_config`. Candidates whose dividers
+/// do not fit the registers are left out. A zero clock or target gives the
+/// table header only.
+pub fn generate_frequency_plan_report(
+    clock_fq_hz: u32,
+    output_targets: &[u32],
+    vco_range: (u32, u32),
+) -> String {
+    let mut report = String::new();
+
+    // Writing to a `String` does not fail
+    let _ = write_report(&mut report, clock_fq_hz, output_targets, vco_range);
+
+    report
+}
+
+fn write_report(
+    report: &mut String,
+    clock_fq_hz: u32,
+    output_targets: &[u32],
+    (vco_min_hz, vco_max_hz): (u32, u32),
+) -> fmt::Result {
+    write!(report, "| VCO (Hz) |")?;
+
+    for (output, target_hz) in output_targets.iter().enumerate() {
+        write!(report, " OUT{} {} Hz |", output + 1, target_hz)?;
+    }
+
+    write!(report, "\n|---:|")?;
+
+    for _ in output_targets {
+        write!(report, "---:|")?;
+    }
+
+    writeln!(report)?;
+
+    if clock_fq_hz == 0 || output_targets.contains(&0) {
+        return Ok(());
+    }
+
+    for vco_fq_hz in (vco_min_hz..=vco_max_hz).step_by(VCO_STEP_HZ as usize) {
+        let feedback_divider = compute_feedback_divider(vco_fq_hz, clock_fq_hz);
+
+        if feedback_divider >> 32 == 0 || feedback_divider >> 32 > 0xFFF {
+            continue;
+        }
+
+        // The chip does not store the lowest fraction byte
+        let actual_vco_hz = vco_frequency(feedback_divider & !0xFF, clock_fq_hz);
+
+        let usable = output_targets.iter().all(|&target_hz| {
+            let od = compute_output_divider(vco_fq_hz, target_hz);
+
+            od >> 32 != 0 && od >> 32 <= 0xFFF
+        });
+
+        if !usable {
+            continue;
+        }
+
+        write!(report, "| {} |", vco_fq_hz)?;
+
+        for &target_hz in output_targets {
+            let od = compute_output_divider(vco_fq_hz, target_hz) & !0xFF;
+
+            write!(
+                report,
+                " {} ({} ppm) |",
+                compute_actual_frequency(actual_vco_hz, od),
+                frequency_error_ppm(target_hz, actual_vco_hz, od)
+            )?;
+        }
+
+        writeln!(report)?;
+    }
+
+    Ok(())
+}
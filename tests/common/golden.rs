@@ -0,0 +1,23 @@
+// Burst `write_config` sends for `DEFAULT_CONFIG_6965`, included by the unit
+// tests in `src/lib.rs` and by `tests/mock_i2c.rs`
+
+/// Start address followed by registers 0x00 - 0x69: 10 MHz in, 2700 MHz VCO,
+/// 40 / 25 / 24 / 28.8 MHz out
+#[rustfmt::skip]
+const GOLDEN_6965_BURST: [u8; 107] = [
+    0x00, // start address
+    0x61, 0x0F, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 0x00
+    0x00, 0xFF, 0x01, 0xC0, 0x00, 0xB6, 0xB4, 0x92, // 0x08
+    0x40, 0x0D, 0x81, 0x82, 0x00, 0x03, 0x84, 0x10, // 0x10
+    0xE0, 0x00, 0x00, 0x00, 0x9F, 0xFD, 0xC8, 0x80, // 0x18
+    0x00, 0x81, 0x03, 0x00, 0x00, 0x00, 0x00, 0x00, // 0x20, OD1 33.75
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x02, 0x10, 0x00, // 0x28
+    0x00, 0x83, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, // 0x30, OD2 54
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0x60, 0x00, // 0x38
+    0x00, 0x81, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, // 0x40, OD3 56.25
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x03, 0x80, 0x00, // 0x48
+    0x00, 0x81, 0x03, 0x80, 0x00, 0x00, 0x00, 0x00, // 0x50, OD4 46.875
+    0x00, 0x00, 0x04, 0x00, 0x00, 0x02, 0xE0, 0x00, // 0x58
+    0x3B, 0x01, 0x3B, 0x01, 0x3B, 0x01, 0x3B, 0x01, // 0x60
+    0xFF, 0xFC, // 0x68
+];
@@ -0,0 +1,693 @@
+//! Driver tests against a simulated chip on a mock I2C bus
+//!
+//! `MockI2c` is a handle to a `Chip` with a register file like the 5P49V (a
+//! write sets the register pointer with its first byte, reads and further
+//! bytes continue from there) that records every transaction for the checks.
+//! The test keeps a second handle to look at the chip while the driver owns
+//! the bus.
+
+use core::cell::RefCell;
+use core::future::Future;
+use core::pin::pin;
+use core::task::{Context, Poll, Waker};
+use std::rc::Rc;
+
+use embassy_sync::blocking_mutex::raw::NoopRawMutex;
+use embedded_hal::i2c::{ErrorKind, ErrorType, Operation};
+
+use renesas_5p49v_rs::events::{
+    pll_event_monitor_task, PllEvent, PllEventChannel, PllEventReceiver,
+};
+use renesas_5p49v_rs::{
+    pll_monitor_task, regs, write_config_multi, Address, Calibrated, ClockOutputConfig, Configured,
+    DriveStrength, Gen5, Gen6, OePolarity, OutputChannel, OutputFormat, Pll5p49v, Pll5p49v5923,
+    Pll5p49v6965, Pll5p49vConfig, Pll5p49vError, Polarity, SlewRate, DEFAULT_CONFIG_6965,
+};
+
+#[cfg(feature = "blocking")]
+use renesas_5p49v_rs::{
+    blocking::Pll5p49vBlocking, PllCalibrationParams, DEFAULT_CALIBRATION_RETRIES,
+};
+
+include!("common/golden.rs");
+
+/// I2C transaction seen by `MockI2c`
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Transaction {
+    /// Register address followed by the written values
+    Write(u8, Vec<u8>),
+    /// Register address and number of bytes read back
+    WriteRead(u8, u8, usize),
+}
+
+struct Chip {
+    regs: [u8; 256],
+    pointer: u8,
+    transactions: Vec<Transaction>,
+    /// Added to the VCO status (register 0x99) after each read of it, a
+    /// non-zero step simulates an unstable calibration
+    vco_status_step: u8,
+}
+
+impl Chip {
+    fn new() -> Rc<RefCell<Chip>> {
+        Rc::new(RefCell::new(Chip {
+            regs: [0; 256],
+            pointer: 0,
+            transactions: Vec::new(),
+            vco_status_step: 0,
+        }))
+    }
+
+    fn run(&mut self, address: u8, operations: &mut [Operation<'_>]) -> Result<(), ErrorKind> {
+        let mut written = Vec::new();
+        let mut read_len = None;
+
+        for operation in operations.iter_mut() {
+            match operation {
+                Operation::Write(bytes) => {
+                    let (&reg, values) = bytes.split_first().expect("empty write");
+
+                    self.pointer = reg;
+                    written = bytes.to_vec();
+
+                    for &value in values {
+                        self.regs[self.pointer as usize] = value;
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+                }
+                Operation::Read(buf) => {
+                    for byte in buf.iter_mut() {
+                        *byte = self.regs[self.pointer as usize];
+
+                        if self.pointer == regs::VCO_STATUS {
+                            self.regs[regs::VCO_STATUS as usize] =
+                                byte.wrapping_add(self.vco_status_step);
+                        }
+
+                        self.pointer = self.pointer.wrapping_add(1);
+                    }
+
+                    read_len = Some(buf.len());
+                }
+            }
+        }
+
+        let transaction = match read_len {
+            Some(len) => Transaction::WriteRead(address, written[0], len),
+            None => Transaction::Write(address, written),
+        };
+
+        self.transactions.push(transaction);
+
+        Ok(())
+    }
+}
+
+/// Bus handle of a `Chip`
+struct MockI2c(Rc<RefCell<Chip>>);
+
+impl ErrorType for MockI2c {
+    type Error = ErrorKind;
+}
+
+impl embedded_hal_async::i2c::I2c for MockI2c {
+    async fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.0.borrow_mut().run(address, operations)
+    }
+}
+
+impl embedded_hal::i2c::I2c for MockI2c {
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.0.borrow_mut().run(address, operations)
+    }
+}
+
+/// OE pin recording every level it is driven to, `true` for high
+struct MockPin(Rc<RefCell<Vec<bool>>>);
+
+impl embedded_hal::digital::ErrorType for MockPin {
+    type Error = core::convert::Infallible;
+}
+
+impl embedded_hal::digital::OutputPin for MockPin {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().push(false);
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.0.borrow_mut().push(true);
+        Ok(())
+    }
+}
+
+/// Poll a future to completion, the driver futures only wait on `Timer`
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+
+        std::thread::yield_now();
+    }
+}
+
+/// Poll a future that never completes, e.g. a monitor task, until `done`
+/// holds, at most one second
+fn run_until<F: Future>(future: F, mut done: impl FnMut() -> bool) {
+    let mut future = pin!(future);
+    let mut cx = Context::from_waker(Waker::noop());
+    let start = std::time::Instant::now();
+
+    while !done() {
+        assert!(start.elapsed().as_secs() < 1, "timed out");
+
+        let _ = future.as_mut().poll(&mut cx);
+        std::thread::yield_now();
+    }
+}
+
+/// Default configuration without the stabilization wait
+const CONFIG: Pll5p49vConfig = Pll5p49vConfig {
+    stabilization_ms: 0,
+    ..DEFAULT_CONFIG_6965
+};
+
+/// Driver on a mock bus with `CONFIG` written, and the simulated chip
+fn configured() -> (Pll5p49v<MockI2c, Gen6, Configured>, Rc<RefCell<Chip>>) {
+    let chip = Chip::new();
+    let pll = Pll5p49v6965::new_unchecked(MockI2c(chip.clone()));
+
+    match block_on(pll.write_config(&CONFIG)) {
+        Ok(pll) => (pll, chip),
+        Err((_, e)) => panic!("write_config: {e:?}"),
+    }
+}
+
+#[test]
+fn set_output_format_bits() {
+    let (mut pll, chip) = configured();
+
+    // Format in bits 7:5, drive strength and slew rate (0x1B) are kept
+    let expected = [
+        (OutputFormat::Lvpecl, 0x1B),
+        (OutputFormat::Lvcmos, 0x3B),
+        (OutputFormat::Hcsl, 0x5B),
+        (OutputFormat::Lvds, 0x7B),
+    ];
+
+    for output in OutputChannel::iter() {
+        for (format, bits) in expected {
+            block_on(pll.set_output_format(output, format)).unwrap();
+
+            let reg = regs::CLK1_CFG_0 + regs::CLK_CFG_STRIDE * output.index() as u8;
+
+            assert_eq!(chip.borrow().regs[reg as usize], bits, "{format:?}");
+        }
+    }
+}
+
+#[test]
+fn set_output_format_lvds_refused_at_1v8() {
+    let (mut pll, _chip) = configured();
+
+    block_on(pll.write_register(regs::OUTPUT_CTRL, 0xFC | regs::OUTPUT_CTRL_V18)).unwrap();
+
+    assert_eq!(
+        block_on(pll.set_output_format(OutputChannel::Out1, OutputFormat::Lvds)),
+        Err(Pll5p49vError::NotApplicableForFormat)
+    );
+}
+
+#[test]
+fn read_modify_write_masks() {
+    let chip = Chip::new();
+    let mut pll = Pll5p49v6965::new_unchecked(MockI2c(chip.clone()));
+
+    // (mask, value, result) on a register holding 0xA5
+    let cases = [(0x00, 0xFF, 0xA5), (0xFF, 0x3C, 0x3C), (0x0F, 0x3C, 0xAC)];
+
+    for (mask, value, result) in cases {
+        chip.borrow_mut().regs[regs::XTAL_X1_LOAD_CAP as usize] = 0xA5;
+
+        block_on(pll.read_modify_write(regs::XTAL_X1_LOAD_CAP, mask, value)).unwrap();
+
+        assert_eq!(
+            chip.borrow().regs[regs::XTAL_X1_LOAD_CAP as usize],
+            result,
+            "mask {mask:#04x}"
+        );
+    }
+}
+
+#[test]
+fn write_config_after_invalidate_cache() {
+    let (pll, chip) = configured();
+
+    let full_burst = chip.borrow_mut().transactions.remove(0);
+    assert!(matches!(&full_burst, Transaction::Write(0x6A, bytes) if bytes.len() == 107));
+
+    // Unchanged registers are not sent again
+    let mut pll = block_on(pll.write_config(&CONFIG)).unwrap();
+    assert_eq!(chip.borrow().transactions, []);
+
+    // A chip that lost its registers still matches the cache
+    pll.invalidate_cache();
+    block_on(pll.write_config(&CONFIG)).unwrap();
+
+    assert_eq!(chip.borrow().transactions, [full_burst]);
+}
+
+#[test]
+fn write_config_golden_burst() {
+    // 10 MHz in, 2700 MHz VCO, 40 / 25 / 24 / 28.8 MHz out
+    let config = Pll5p49vConfig {
+        clock_fq_hz: 10000000,
+        vco_fq_hz: 2700000000,
+        outputs: [
+            Some(40000000),
+            Some(25000000),
+            Some(24000000),
+            Some(28800000),
+        ],
+        ..CONFIG
+    };
+
+    let chip = Chip::new();
+    let pll = Pll5p49v6965::new_unchecked(MockI2c(chip.clone()));
+
+    if let Err((_, e)) = block_on(pll.write_config(&config)) {
+        panic!("write_config: {e:?}");
+    }
+
+    assert_eq!(
+        chip.borrow().transactions,
+        [Transaction::Write(0x6A, GOLDEN_6965_BURST.to_vec())]
+    );
+}
+
+#[test]
+fn write_config_multi_matches_write_config() {
+    let config = CONFIG.with_output_disabled(OutputChannel::Out3);
+
+    let chip = Chip::new();
+    let pll = Pll5p49v6965::new_unchecked(MockI2c(chip.clone()));
+
+    if let Err((_, e)) = block_on(pll.write_config(&config)) {
+        panic!("write_config: {e:?}");
+    }
+
+    let multi_chip = Chip::new();
+    let configs = [(Address::Default, config)];
+
+    block_on(write_config_multi(
+        &mut MockI2c(multi_chip.clone()),
+        &configs,
+    ))
+    .unwrap();
+
+    // The divider registers of OUT3 are left out in both
+    assert_eq!(multi_chip.borrow().transactions, chip.borrow().transactions);
+}
+
+#[test]
+fn calibrate_vco_transactions() {
+    let (pll, chip) = configured();
+    chip.borrow_mut().transactions.clear();
+
+    let cal_start = chip.borrow().regs[regs::CAL_START as usize] & !regs::CAL_START_BIT;
+
+    if let Err((_, e)) = block_on(pll.calibrate_vco()) {
+        panic!("calibrate_vco: {e:?}");
+    }
+
+    // 0-1-0 on CAL_START (register 0x1C, bit 7), then the VCO index read twice
+    let rmw = |value| {
+        [
+            Transaction::WriteRead(0x6A, regs::CAL_START, 1),
+            Transaction::Write(0x6A, vec![regs::CAL_START, value]),
+        ]
+    };
+    let mut expected = Vec::new();
+    expected.extend(rmw(cal_start));
+    expected.extend(rmw(cal_start | regs::CAL_START_BIT));
+    expected.extend(rmw(cal_start));
+    expected.extend(vec![Transaction::WriteRead(0x6A, regs::VCO_STATUS, 1); 2]);
+
+    assert_eq!(chip.borrow().transactions, expected);
+}
+
+#[test]
+fn switch_frequency_plan_calibrates_gen6_only() {
+    let mut config = CONFIG;
+    config.outputs[0] = Some(48000000);
+
+    let output_en = |chip: &Rc<RefCell<Chip>>| chip.borrow().regs[regs::OUTPUT_EN as usize];
+    let vco_reads = |chip: &Rc<RefCell<Chip>>| {
+        chip.borrow()
+            .transactions
+            .iter()
+            .filter(|t| **t == Transaction::WriteRead(0x6A, regs::VCO_STATUS, 1))
+            .count()
+    };
+
+    let (pll, chip) = configured();
+    let enabled = output_en(&chip);
+    chip.borrow_mut().transactions.clear();
+
+    // Outputs off first, the calibrated driver comes back
+    let _: Pll5p49v<MockI2c, Gen6, Calibrated> = match block_on(pll.switch_frequency_plan(&config))
+    {
+        Ok(pll) => pll,
+        Err((_, e)) => panic!("switch_frequency_plan: {e:?}"),
+    };
+
+    assert_eq!(
+        chip.borrow().transactions[0],
+        Transaction::Write(0x6A, vec![regs::OUTPUT_EN, enabled & !regs::OUTPUT_EN_MASK])
+    );
+    assert_eq!(vco_reads(&chip), 2);
+    assert_eq!(output_en(&chip), enabled);
+
+    let chip = Chip::new();
+    let pll =
+        match block_on(Pll5p49v5923::new_unchecked(MockI2c(chip.clone())).write_config(&CONFIG)) {
+            Ok(pll) => pll,
+            Err((_, e)) => panic!("write_config: {e:?}"),
+        };
+
+    let _: Pll5p49v<MockI2c, Gen5, Calibrated> = match block_on(pll.switch_frequency_plan(&config))
+    {
+        Ok(pll) => pll,
+        Err((_, e)) => panic!("switch_frequency_plan: {e:?}"),
+    };
+
+    assert_eq!(vco_reads(&chip), 0);
+    assert_eq!(output_en(&chip), enabled);
+}
+
+#[test]
+fn trim_output_frequency_clears_int_mode() {
+    let (mut pll, chip) = configured();
+
+    // OUT2 runs from the integer divider 54
+    assert_eq!(chip.borrow().regs[regs::OD2_CONTROL as usize], 0x83);
+
+    block_on(pll.trim_output_frequency(OutputChannel::Out2, -100)).unwrap();
+
+    // 54.0054: INT_MODE cleared, fraction 0x0161EE00 in 0x32 - 0x35
+    let od2 = regs::OD2_CONTROL as usize;
+    assert_eq!(
+        chip.borrow().regs[od2..od2 + 5],
+        [0x81, 0x00, 0x05, 0x87, 0xB8]
+    );
+
+    // The cache knows the trimmed registers, `write_config` restores them
+    chip.borrow_mut().transactions.clear();
+    block_on(pll.write_config(&CONFIG)).unwrap();
+
+    assert_eq!(
+        chip.borrow().transactions,
+        [
+            Transaction::Write(0x6A, vec![regs::OD2_CONTROL, 0x83]),
+            Transaction::Write(0x6A, vec![regs::OD2_CONTROL + 2, 0x00, 0x00, 0x00]),
+        ]
+    );
+}
+
+#[test]
+fn oe_pin_follows_polarity() {
+    let levels = Rc::new(RefCell::new(Vec::new()));
+    let pll =
+        Pll5p49v6965::new_unchecked(MockI2c(Chip::new())).with_oe_pin(MockPin(levels.clone()));
+
+    let config = Pll5p49vConfig {
+        oe_polarity: OePolarity::ActiveLow,
+        ..CONFIG
+    };
+
+    // Disabled at the power-up active-high polarity, enabled at the new one
+    let mut pll = match block_on(pll.write_config(&config)) {
+        Ok(pll) => pll,
+        Err((_, e)) => panic!("write_config: {e:?}"),
+    };
+
+    assert_eq!(*levels.borrow(), [false, false]);
+
+    pll.assert_oe::<ErrorKind>().unwrap();
+
+    assert_eq!(*levels.borrow(), [false, false, true]);
+}
+
+#[test]
+fn configure_all_outputs_matches_write_config() {
+    let configs = [
+        ClockOutputConfig {
+            drive_strength: DriveStrength::Ma8,
+            slew_rate: SlewRate::Slow,
+            ..ClockOutputConfig::DEFAULT
+        },
+        ClockOutputConfig {
+            format: OutputFormat::Hcsl,
+            polarity: Polarity::Inverted,
+            ..ClockOutputConfig::DEFAULT
+        },
+        ClockOutputConfig {
+            format: OutputFormat::Lvds,
+            slew_rate: SlewRate::Slow,
+            ..ClockOutputConfig::DEFAULT
+        },
+        ClockOutputConfig {
+            enabled: false,
+            ..ClockOutputConfig::DEFAULT
+        },
+    ];
+
+    let (mut pll, chip) = configured();
+    block_on(pll.configure_all_outputs(configs)).unwrap();
+
+    // The same settings in a configuration give the same registers
+    let config = Pll5p49vConfig {
+        clock_outputs: configs,
+        ..CONFIG
+    };
+    let written = Chip::new();
+    let pll = Pll5p49v6965::new_unchecked(MockI2c(written.clone()));
+
+    if let Err((_, e)) = block_on(pll.write_config(&config)) {
+        panic!("write_config: {e:?}");
+    }
+
+    let outputs = regs::CLK1_CFG_0 as usize..=regs::OUTPUT_EN as usize;
+    assert_eq!(
+        chip.borrow().regs[outputs.clone()],
+        written.borrow().regs[outputs]
+    );
+}
+
+/// `DelayNs` returning at once, the mock chip does not need the waits
+#[cfg(feature = "blocking")]
+struct NoDelay;
+
+#[cfg(feature = "blocking")]
+impl embedded_hal::delay::DelayNs for NoDelay {
+    fn delay_ns(&mut self, _ns: u32) {}
+}
+
+/// Blocking driver on a mock bus with `CONFIG` written, and the simulated chip
+#[cfg(feature = "blocking")]
+fn configured_blocking() -> (Pll5p49vBlocking<MockI2c, Gen6>, Rc<RefCell<Chip>>) {
+    let chip = Chip::new();
+    let mut pll = Pll5p49vBlocking::new_unchecked(MockI2c(chip.clone()));
+
+    pll.write_config_blocking(&CONFIG, &mut NoDelay).unwrap();
+    chip.borrow_mut().transactions.clear();
+
+    (pll, chip)
+}
+
+/// Delay adding up the requested waits, ns
+#[cfg(feature = "blocking")]
+struct TotalDelay(u64);
+
+#[cfg(feature = "blocking")]
+impl embedded_hal::delay::DelayNs for TotalDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.0 += ns as u64;
+    }
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn write_config_blocking_matches_async() {
+    let config = CONFIG.with_output_disabled(OutputChannel::Out3);
+
+    let chip = Chip::new();
+    let pll = Pll5p49v6965::new_unchecked(MockI2c(chip.clone()));
+
+    if let Err((_, e)) = block_on(pll.write_config(&config)) {
+        panic!("write_config: {e:?}");
+    }
+
+    let blocking_chip = Chip::new();
+    let mut blocking = Pll5p49vBlocking::<_, Gen6>::new_unchecked(MockI2c(blocking_chip.clone()));
+    let mut delay = TotalDelay(0);
+
+    let config = Pll5p49vConfig {
+        stabilization_ms: 10,
+        ..config
+    };
+
+    blocking.write_config_blocking(&config, &mut delay).unwrap();
+
+    // The divider registers of OUT3 are left out in both
+    assert_eq!(
+        blocking_chip.borrow().transactions,
+        chip.borrow().transactions
+    );
+    assert_eq!(delay.0, 10000000);
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn calibrate_vco_blocking_matches_async() {
+    let (pll, chip) = configured();
+    chip.borrow_mut().transactions.clear();
+
+    if let Err((_, e)) = block_on(pll.calibrate_vco()) {
+        panic!("calibrate_vco: {e:?}");
+    }
+
+    let (mut blocking, blocking_chip) = configured_blocking();
+    blocking.calibrate_vco_blocking(&mut NoDelay).unwrap();
+
+    assert_eq!(
+        blocking_chip.borrow().transactions,
+        chip.borrow().transactions
+    );
+}
+
+#[cfg(feature = "blocking")]
+#[test]
+fn calibrate_vco_blocking_unstable() {
+    let (mut pll, chip) = configured_blocking();
+
+    // Every read sees the next band index
+    chip.borrow_mut().vco_status_step = 0x08;
+
+    assert_eq!(
+        pll.calibrate_vco_blocking(&mut NoDelay),
+        Err(Pll5p49vError::CalibrationUnstable {
+            retries: DEFAULT_CALIBRATION_RETRIES,
+            last_index: 7,
+        })
+    );
+
+    // 0-1-0 pulse and two index reads per attempt
+    assert_eq!(
+        chip.borrow().transactions.len(),
+        (DEFAULT_CALIBRATION_RETRIES as usize + 1) * 8
+    );
+
+    // A stable index is returned
+    chip.borrow_mut().vco_status_step = 0;
+
+    assert_eq!(
+        pll.calibrate_vco_blocking_with_params(&mut NoDelay, &PllCalibrationParams::DEFAULT, 0),
+        Ok(8)
+    );
+}
+
+#[test]
+fn pll_monitor_task_restores_lost_configuration() {
+    let (pll, chip) = configured();
+    let mut pll = block_on(pll.calibrate_vco()).unwrap();
+
+    let feedback = regs::FEEDBACK_DIV_INT_HI as usize..=regs::FEEDBACK_DIV_FRAC_2 as usize;
+    let expected = chip.borrow().regs[feedback.clone()].to_vec();
+
+    // A brownout, the registers are back at their power-up values
+    chip.borrow_mut().regs = [0; 256];
+    chip.borrow_mut().transactions.clear();
+
+    // Done once the full burst is written and the VCO index read after the calibration
+    run_until(pll_monitor_task(&mut pll, &CONFIG, 1), || {
+        let chip = chip.borrow();
+        let burst = chip
+            .transactions
+            .iter()
+            .position(|t| matches!(t, Transaction::Write(0x6A, bytes) if bytes.len() == 107));
+
+        burst.is_some_and(|burst| {
+            chip.transactions[burst..]
+                .iter()
+                .filter(|t| **t == Transaction::WriteRead(0x6A, regs::VCO_STATUS, 1))
+                .count()
+                == 2
+        })
+    });
+
+    assert_eq!(chip.borrow().regs[feedback], expected);
+}
+
+#[test]
+fn pll_event_monitor_task_events() {
+    let (pll, chip) = configured();
+    let mut pll = block_on(pll.calibrate_vco()).unwrap();
+
+    let events = PllEventChannel::<NoopRawMutex, ErrorKind, 4>::new();
+    let mut receiver = PllEventReceiver::new(&events);
+
+    chip.borrow_mut().transactions.clear();
+
+    // The first check records the VCO index, then the band changes
+    let mut changed = false;
+
+    run_until(
+        pll_event_monitor_task(&mut pll, &CONFIG, 1, &events),
+        || {
+            let status_read = Transaction::WriteRead(0x6A, regs::VCO_STATUS, 1);
+
+            if !changed && chip.borrow().transactions.contains(&status_read) {
+                chip.borrow_mut().regs[regs::VCO_STATUS as usize] = 8 << 3;
+                changed = true;
+            }
+
+            !events.is_empty()
+        },
+    );
+
+    assert_eq!(
+        block_on(receiver.next()),
+        PllEvent::VcoIndexChanged {
+            previous: 0,
+            current: 8
+        }
+    );
+
+    // A brownout, the registers are back at their power-up values
+    chip.borrow_mut().regs = [0; 256];
+
+    run_until(
+        pll_event_monitor_task(&mut pll, &CONFIG, 1, &events),
+        || events.len() == 2,
+    );
+
+    assert_eq!(block_on(receiver.next()), PllEvent::ConfigurationLost);
+    assert_eq!(
+        block_on(receiver.next()),
+        PllEvent::CalibrationComplete { vco_index: 0 }
+    );
+}